@@ -0,0 +1,119 @@
+//! Measures the peak heap memory of the subsumfree sequence engines.
+//!
+//! Run with `cargo bench --bench memory -- [length...]`. Every length is run
+//! for both engines, reporting the elapsed time, the peak number of bytes on
+//! the heap and the peak per element.
+//!
+//! The seed `[length, length + 1]` makes every number below `2 * length`
+//! part of the sequence, while the expressions of every element stay pending.
+//! This isolates the memory the engines need per element from the time spent
+//! merging expressions. The seed `[2, 3, 10]` is a realistic one, where
+//! expressions are resolved all the time while the pending ones fill the
+//! heap. Its runs take long, so it is only run up to 1000 elements.
+//!
+//! Before the expression streams shared the element store, at b51a9f0, every
+//! stream cloned the prefix. The same benchmark reported for the combination
+//! engine, the word engine being alike:
+//!
+//! ```text
+//! seed        length   before                  after
+//! [n, n + 1]  1000     8.1 MB    11 ms         155 KB   0.6 ms
+//! [n, n + 1]  10000    801 MB    946 ms        2.5 MB   3.0 ms
+//! [n, n + 1]  100000   out of memory           19.9 MB  39 ms
+//! [2, 3, 10]  100      77 KB     6.3 ms        71 KB    4.4 ms
+//! [2, 3, 10]  1000     7.0 MB    18 s          5.6 MB   4.8 s
+//! ```
+//!
+//! The engines at b51a9f0 also merged some expressions out of order, so for
+//! `[2, 3, 10]` they generated a different sequence, which only allows to
+//! compare the orders of magnitude.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use sequence::combinatorics::combination::Sequence as CombinationSequence;
+use sequence::combinatorics::word::Sequence as WordSequence;
+
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn measure<I>(name: &str, length: usize, iterator: I)
+where
+    I: Iterator<Item = usize>,
+{
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let start = Instant::now();
+    let last = iterator.take(length).last();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    println!(
+        "{:<12} {:>8} {:>12?} {:>12} {:>10.1} {:?}",
+        name,
+        length,
+        elapsed,
+        peak,
+        peak as f64 / length as f64,
+        last
+    );
+}
+
+/// The longest run with a realistic seed.
+const REALISTIC: usize = 1_000;
+
+/// The seeds that are measured for `length` elements.
+fn seeds(length: usize) -> Vec<Vec<usize>> {
+    let mut seeds = vec![vec![length, length + 1]];
+    if length <= REALISTIC {
+        seeds.push(vec![2, 3, 10]);
+    }
+    seeds
+}
+
+fn main() {
+    let lengths: Vec<usize> = env::args()
+        .skip(1)
+        .filter_map(|argument| argument.parse().ok())
+        .collect();
+    let lengths = if lengths.is_empty() {
+        vec![100, 1_000, 10_000, 100_000]
+    } else {
+        lengths
+    };
+
+    println!(
+        "{:<12} {:>8} {:>12} {:>12} {:>10} last",
+        "engine", "length", "elapsed", "peak bytes", "per element"
+    );
+    for length in lengths {
+        for seed in seeds(length) {
+            println!("seed {:?}", seed);
+            measure(
+                "combination",
+                length,
+                CombinationSequence::new(seed.clone()),
+            );
+            measure("word", length, WordSequence::new(seed));
+        }
+    }
+}
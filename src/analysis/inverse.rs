@@ -0,0 +1,161 @@
+//! The `inverse` module searches the parameters that generate a given list of
+//! numbers, e.g. one taken from a paper.
+//!
+//! Every proper prefix of the list is tried as initial segment, together with
+//! every [`Mode`] and number of summands of a [`Search`]. The parameters that
+//! reproduce the whole list, and no other numbers below its last element,
+//! are returned. Since a longer prefix of a greedy sequence trivially
+//! reproduces it as well, only the shortest initial segment is returned for
+//! every mode and number of summands.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::Mode;
+//! # use sequence::analysis::inverse::{Candidate, Search};
+//! let candidates = Search::new().infer(&[1, 2, 3, 4, 5, 13, 14, 15]);
+//! let expected = Candidate { initial: vec![1], mode: Mode::Distinct, k: 3 };
+//! assert_eq!(candidates.first(), Option::Some(&expected));
+//! ```
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::{Config, Mode, sequence};
+
+/// Parameters that generate a list of numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// The initial segment.
+    pub initial: Vec<usize>,
+    /// How often an element may occur in an expression.
+    pub mode: Mode,
+    /// The number of summands.
+    pub k: usize,
+}
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} k={} {:?}", self.mode, self.k, self.initial)
+    }
+}
+
+/// The parameters that are tried by an inverse search.
+///
+/// A `Search` is built by chaining its methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search {
+    modes: Vec<Mode>,
+    summands: RangeInclusive<usize>,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            modes: vec![Mode::Distinct, Mode::Duplicate, Mode::Xor, Mode::Product],
+            summands: 1..=5,
+        }
+    }
+}
+
+impl Search {
+    /// Creates a `Search` over the distinct, duplicate, XOR and product
+    /// sequences with one up to five summands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tries the given modes instead.
+    pub fn modes<I>(mut self, modes: I) -> Self
+    where
+        I: IntoIterator<Item = Mode>,
+    {
+        self.modes = modes.into_iter().collect();
+        self
+    }
+
+    /// Tries the given numbers of summands instead.
+    pub fn summands(mut self, summands: RangeInclusive<usize>) -> Self {
+        self.summands = summands;
+        self
+    }
+
+    /// The candidates with the shortest initial segments that reproduce
+    /// `numbers`, ordered by the length of their initial segment, then by
+    /// mode and number of summands.
+    pub fn infer(&self, numbers: &[usize]) -> Vec<Candidate> {
+        let Option::Some(last) = numbers.last() else {
+            return Vec::new();
+        };
+        let mut candidates = Vec::new();
+        for length in 1..numbers.len() {
+            for mode in &self.modes {
+                for k in self.summands.clone() {
+                    if candidates
+                        .iter()
+                        .any(|c: &Candidate| c.mode == *mode && c.k == k)
+                    {
+                        continue;
+                    }
+                    let initial = numbers[..length].to_vec();
+                    let config = Config::new().ceiling(last + 1).summands([k]);
+                    let reproduces = sequence(initial.clone(), mode.clone(), config)
+                        .map(|iterator| {
+                            iterator.take(numbers.len() + 1).eq(numbers.iter().cloned())
+                        })
+                        .unwrap_or(false);
+                    if reproduces {
+                        candidates.push(Candidate {
+                            initial,
+                            mode: mode.clone(),
+                            k,
+                        });
+                    }
+                }
+            }
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Candidate, Search};
+    use crate::Mode;
+
+    #[test]
+    fn odious_numbers_are_xor_sequences() {
+        let odious = [1, 2, 4, 7, 8, 11, 13, 14, 16, 19];
+        let candidates = Search::new().modes([Mode::Xor]).infer(&odious);
+        let expected = vec![
+            Candidate {
+                initial: vec![1],
+                mode: Mode::Xor,
+                k: 2,
+            },
+            Candidate {
+                initial: vec![1, 2, 4, 7],
+                mode: Mode::Xor,
+                k: 4,
+            },
+        ];
+        assert_eq!(candidates, expected);
+    }
+
+    #[test]
+    fn longer_prefixes_are_tried() {
+        let candidates = Search::new()
+            .modes([Mode::Duplicate])
+            .summands(2..=2)
+            .infer(&[1, 4, 6, 9, 11, 14]);
+        let initials: Vec<Vec<usize>> = candidates.into_iter().map(|c| c.initial).collect();
+        assert_eq!(initials, vec![vec![1, 4]]);
+    }
+
+    #[test]
+    fn other_lists_have_no_candidates() {
+        assert!(Search::new().infer(&[]).is_empty());
+        assert!(Search::new().infer(&[1]).is_empty());
+        assert!(Search::new().infer(&[1, 3, 2]).is_empty());
+    }
+}
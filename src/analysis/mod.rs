@@ -0,0 +1,169 @@
+//! The `analysis` module computes growth statistics of sequences.
+//!
+//! The statistics are computed while the sequence is generated, at every
+//! multiple of a step. At such a checkpoint `x` they consist of
+//!
+//! 1. the counting function `A(x) = #{a ≤ x}`,
+//! 2. the density `A(x)/x`,
+//! 3. the density of the window of the last step and
+//! 4. the maximal gap between consecutive elements so far.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::analysis::Rows;
+//! let odd = (1..).step_by(2).take_while(|n| *n < 100);
+//! let last = Rows::new(odd, 10, 100).last().unwrap();
+//! assert_eq!(last.count, 50);
+//! assert_eq!(last.density, 0.5);
+//! assert_eq!(last.maximal_gap, 2);
+//! ```
+
+pub mod inverse;
+
+use std::fmt::Display;
+use std::iter::Peekable;
+
+use crate::tools::character::Character;
+
+/// The statistics of a sequence at a checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// The checkpoint.
+    pub x: usize,
+    /// The number of elements of at most `x`.
+    pub count: usize,
+    /// The number of elements divided by `x`.
+    pub density: f64,
+    /// The number of elements in the window that ends at `x`, divided by the
+    /// size of the window.
+    pub window_density: f64,
+    /// The maximal difference between consecutive elements of at most `x`.
+    pub maximal_gap: usize,
+}
+
+impl Row {
+    /// The header of a table of rows, aligned with the `Display` of a row.
+    pub fn header() -> String {
+        format!(
+            "{:>10} {:>10} {:>10} {:>10} {:>10}",
+            "x", "A(x)", "A(x)/x", "window", "max gap"
+        )
+    }
+}
+
+impl Display for Row {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>10} {:>10} {:>10.6} {:>10.6} {:>10}",
+            self.x, self.count, self.density, self.window_density, self.maximal_gap
+        )
+    }
+}
+
+/// Iterates over the statistics of an increasing sequence at every multiple
+/// of `step` up to `ceiling`.
+///
+/// The sequence is consumed lazily, so any engine can be analysed without
+/// storing its elements.
+#[derive(Debug)]
+pub struct Rows<I>
+where
+    I: Iterator<Item = usize>,
+{
+    sequence: Peekable<I>,
+    step: usize,
+    ceiling: usize,
+    x: usize,
+    count: usize,
+    previous: Option<usize>,
+    maximal_gap: usize,
+}
+
+impl<I> Rows<I>
+where
+    I: Iterator<Item = usize>,
+{
+    /// Creates the statistics of `sequence` at every multiple of `step` up to
+    /// `ceiling`.
+    ///
+    /// # Panics
+    /// Panics when `step` is zero.
+    pub fn new(sequence: I, step: usize, ceiling: usize) -> Self {
+        assert!(step > 0, "the step must be at least 1");
+        Self {
+            sequence: sequence.peekable(),
+            step,
+            ceiling,
+            x: 0,
+            count: 0,
+            previous: Option::None,
+            maximal_gap: 0,
+        }
+    }
+}
+
+impl<I> Iterator for Rows<I>
+where
+    I: Iterator<Item = usize>,
+{
+    type Item = Row;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.x + self.step;
+        if x > self.ceiling {
+            return Option::None;
+        }
+        self.x = x;
+        let mut window = 0;
+        while let Option::Some(element) = self.sequence.next_if(|element| *element <= x) {
+            if let Option::Some(previous) = self.previous {
+                self.maximal_gap = self.maximal_gap.max(element - previous);
+            }
+            self.previous = Option::Some(element);
+            window += 1;
+        }
+        self.count += window;
+        Option::Some(Row {
+            x,
+            count: self.count,
+            density: self.count as f64 / x as f64,
+            window_density: window as f64 / self.step as f64,
+            maximal_gap: self.maximal_gap,
+        })
+    }
+}
+
+/// The asymptotic density of a sequence with a `Character`, i.e. the
+/// fraction of residues that repeat.
+///
+/// ```
+/// # use sequence::analysis::asymptotic_density;
+/// # use sequence::tools::character::determine_character;
+/// let seq: Vec<usize> = (1..100).filter(|n| n % 4 == 1 || n % 4 == 2).collect();
+/// let character = determine_character(&seq).unwrap();
+/// assert_eq!(asymptotic_density(&character), 0.5);
+/// ```
+pub fn asymptotic_density(character: &Character) -> f64 {
+    character.repeating().len() as f64 / character.modulus() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_count_the_elements_per_window() {
+        let sequence = [1, 2, 3, 10, 11, 25];
+        let rows: Vec<Row> = Rows::new(sequence.into_iter(), 10, 30).collect();
+
+        let counts: Vec<usize> = rows.iter().map(|row| row.count).collect();
+        let windows: Vec<f64> = rows.iter().map(|row| row.window_density).collect();
+        let gaps: Vec<usize> = rows.iter().map(|row| row.maximal_gap).collect();
+
+        assert_eq!(counts, vec![4, 5, 6]);
+        assert_eq!(windows, vec![0.4, 0.1, 0.1]);
+        assert_eq!(gaps, vec![7, 7, 14]);
+    }
+}
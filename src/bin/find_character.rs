@@ -1,32 +1,58 @@
+use std::process;
+
 use clap::Parser;
-use sequence::sequence;
-use sequence::tools::character::determine_character;
+use sequence::cli::{Arguments, Jobs};
 
 #[derive(Parser)]
 struct Input {
-    #[arg(short, long, default_value_t = 500)]
-    length: usize,
-    #[arg(short, long, default_value_t = 1000)]
-    ceiling: usize,
-    #[arg(short, long, default_value_t = false)]
-    duplicate: bool,
+    #[command(flatten)]
+    arguments: Arguments,
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
-    initial: Vec<usize>,
+    /// Read one job per line from a file, or from standard input for `-`,
+    /// and write one record per job.
+    #[arg(short, long)]
+    batch: Option<String>,
 }
 
 fn main() {
     let input = Input::parse();
 
-    let iterator: Box<dyn Iterator<Item = usize>> =
-        sequence(input.initial, input.ceiling, input.duplicate);
-    let seq: Vec<usize> = iterator.take(input.length).collect();
-    if input.verbose {
-        println!("{} {:?}", seq.len(), seq);
+    match &input.batch {
+        Option::Some(path) => batch(path, input.verbose),
+        Option::None => match find(&input.arguments, input.verbose) {
+            Ok((record, warning)) => {
+                println!("{}", record);
+                if let Option::Some(warning) = warning {
+                    eprintln!("warning: {}", warning);
+                }
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        },
     }
+}
+
+fn find(arguments: &Arguments, verbose: bool) -> Result<(String, Option<String>), String> {
+    let generated = arguments.generate().map_err(|error| error.to_string())?;
+    let seq = &generated.elements;
 
-    match determine_character(&seq) {
-        Option::Some(character) => println!("{}", character),
-        Option::None => println!("?"),
+    let mut record = String::new();
+    if verbose {
+        record.push_str(&format!("{} {:?} ", seq.len(), seq));
     }
+    match arguments
+        .describe_character(seq)
+        .map_err(|error| error.to_string())?
+    {
+        Option::Some(character) => record.push_str(&character),
+        Option::None => record.push('?'),
+    }
+    Ok((record, generated.warning()))
+}
+
+fn batch(path: &str, verbose: bool) {
+    Jobs::<Arguments>::run(path, |arguments| find(&arguments, verbose));
 }
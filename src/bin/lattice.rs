@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::process;
+
+use clap::Parser;
+use sequence::Config;
+use sequence::budget::Budgeted;
+use sequence::cli::{Jobs, Limits};
+use sequence::lattice::{Grid, Order, Point, Sequence};
+
+#[derive(Parser)]
+struct Input {
+    /// The ceiling below which the degrees of the points lie.
+    #[arg(short, long, default_value_t = 50)]
+    ceiling: usize,
+    /// The numbers of summands that expressions may not have, e.g. `2,3`.
+    /// Defaults to the length of the initial segment.
+    #[arg(short, long, value_delimiter = ',')]
+    k: Vec<usize>,
+    /// The order in which the points are added.
+    #[arg(short, long, value_enum, default_value_t = Order::GradedLexicographic)]
+    order: Order,
+    /// Write the points as a PPM image.
+    #[arg(long)]
+    ppm: Option<PathBuf>,
+    /// Write the points as an SVG image.
+    #[arg(long)]
+    svg: Option<PathBuf>,
+    /// Read one job per line from a file, or from standard input for `-`,
+    /// and write one record per job.
+    #[arg(short, long)]
+    batch: Option<String>,
+    #[command(flatten)]
+    limits: Limits,
+    /// The initial segment of points, e.g. `0,1 1,0`.
+    #[arg(value_parser = parse_point)]
+    initial: Vec<Point>,
+}
+
+fn parse_point(point: &str) -> Result<Point, String> {
+    point
+        .split(',')
+        .map(|coordinate| coordinate.trim().parse::<usize>())
+        .collect::<Result<Point, _>>()
+        .map_err(|error| format!("invalid point {}: {}", point, error))
+}
+
+fn main() {
+    let input = Input::parse();
+
+    match input.batch.clone() {
+        Option::Some(path) => batch(&path),
+        Option::None => match run(input) {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn run(input: Input) -> Result<Vec<String>, String> {
+    let config = Config::new()
+        .ceiling(input.ceiling)
+        .summands(input.k.iter().cloned());
+    let config = input.limits.configure(config);
+    let mut sequence = Sequence::try_with_config(input.initial, input.order, config)
+        .map_err(|error| error.to_string())?;
+    let points: Vec<Point> = sequence.by_ref().collect();
+    if let Option::Some(exhausted) = sequence.exhausted() {
+        eprintln!(
+            "warning: stopped early after {} points: {}",
+            points.len(),
+            exhausted
+        );
+    }
+    let mut lines = vec![format!("{} {:?}", points.len(), points)];
+
+    // the box in which every point has a degree below the ceiling
+    let dimension = points[0].len();
+    let side = input.ceiling / dimension.max(1);
+    let grid = Grid::new(vec![side; dimension], &points);
+    for axis in 0..dimension {
+        match grid.period(axis) {
+            Option::Some(info) => lines.push(format!("axis {}: {}", axis, info)),
+            Option::None => lines.push(format!("axis {}: ?", axis)),
+        }
+    }
+
+    if let Option::Some(path) = &input.ppm {
+        write_image(path, |file| grid.write_ppm(file))?;
+    }
+    if let Option::Some(path) = &input.svg {
+        write_image(path, |file| grid.write_svg(file))?;
+    }
+    Ok(lines)
+}
+
+fn write_image<F>(path: &PathBuf, write: F) -> Result<(), String>
+where
+    F: FnOnce(&mut File) -> std::io::Result<()>,
+{
+    File::create(path)
+        .and_then(|mut file| write(&mut file))
+        .map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+fn batch(path: &str) {
+    Jobs::<Input>::run(path, |input| {
+        run(input).map(|lines| (lines.join("\t"), Option::None))
+    });
+}
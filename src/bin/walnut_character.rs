@@ -1,36 +1,87 @@
 use std::io::{self};
+use std::process;
 
 use clap::Parser;
-use sequence::sequence;
-use sequence::tools::character::determine_character;
+use sequence::Mode;
+use sequence::cli::{Arguments, Jobs};
+use sequence::combinatorics::form::LinearForm;
 
 #[derive(Parser)]
 struct Input {
-    #[arg(short, long, default_value_t = 500)]
-    length: usize,
-    #[arg(short, long, default_value_t = 1000)]
-    ceiling: usize,
-    #[arg(short, long, default_value_t = false)]
-    duplicate: bool,
+    #[command(flatten)]
+    arguments: Arguments,
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
-    initial: Vec<usize>,
+    /// Read one job per line from a file, or from standard input for `-`,
+    /// and write one script per job. The sequence of the n-th job is called
+    /// `seqn`.
+    #[arg(short, long)]
+    batch: Option<String>,
 }
 
 fn main() {
     let input = Input::parse();
 
-    let seq: Vec<usize> = sequence(input.initial, input.ceiling, input.duplicate)
-        .take(input.length)
-        .collect();
-    if input.verbose {
+    match &input.batch {
+        Option::Some(path) => batch(path, input.verbose),
+        Option::None => {
+            if let Err(error) = walnut(&input.arguments, "seq", input.verbose) {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn walnut(arguments: &Arguments, name: &str, verbose: bool) -> Result<(), String> {
+    let generated = arguments.generate().map_err(|error| error.to_string())?;
+    if let Option::Some(warning) = generated.warning() {
+        eprintln!("warning: {}: {}", name, warning);
+    }
+    let seq = generated.elements;
+    let form = form(arguments)?;
+    if verbose {
         println!("{} {:?}", seq.len(), seq);
     }
 
-    match determine_character(&seq) {
+    match arguments.character(&seq, form) {
         Option::Some(character) => {
-            let _ = character.write_walnut(&"seq", &mut io::stdout());
+            let _ = character.write_walnut(name, &mut io::stdout());
         }
         Option::None => println!("?"),
     }
+    Ok(())
+}
+
+/// The linear form whose solutions the Walnut script rules out, or why the
+/// selected mode has none.
+fn form(arguments: &Arguments) -> Result<LinearForm, String> {
+    if let Option::Some(form) = arguments.form() {
+        return Ok(form);
+    }
+    Err(match arguments.mode() {
+        Mode::Bounded(multiplicity) => format!(
+            "sums with up to {} copies of an element are not a single linear form",
+            multiplicity
+        ),
+        Mode::Xor => "xor sums are not a linear form".to_string(),
+        Mode::Product => "products are not a linear form".to_string(),
+        Mode::Linear(coefficients) => format!("{:?} is not a valid linear form", coefficients),
+        Mode::Distinct | Mode::Duplicate => format!(
+            "the numbers of summands {:?} are not a single linear form",
+            arguments.k
+        ),
+    })
+}
+
+fn batch(path: &str, verbose: bool) {
+    Jobs::<Arguments>::each(path, |index, job| {
+        let written = job
+            .parsed
+            .and_then(|arguments| walnut(&arguments, &format!("seq{}", index + 1), verbose));
+        if let Err(error) = written {
+            eprintln!("error: job {} ({}): {}", index + 1, job.line, error);
+        }
+        println!();
+    });
 }
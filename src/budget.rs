@@ -0,0 +1,430 @@
+//! The `budget` module bounds the work that goes into generating a sequence.
+//!
+//! Besides the ceiling of a [`Config`], a [`Budget`] limits the
+//! wall clock time, the number of candidates that are examined and the size
+//! of the heap of pending expressions. When a budget runs out, the sequence
+//! simply ends, and its [`Budgeted::exhausted`] method tells why, so that
+//! the elements found so far can be used as a partial result.
+//!
+//! While they work, the engines keep [`Counters`], which they expose through
+//! [`Instrumented::counters`] and which a [`Progress`] reports periodically.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::{Config, Mode, sequence};
+//! # use sequence::budget::{Budget, Exhaustion};
+//! let config = Config::new().budget(Budget::new().candidates(9));
+//! let mut iterator = sequence(vec![1, 2, 3], Mode::Distinct, config).unwrap();
+//! let elements: Vec<usize> = iterator.by_ref().collect();
+//! assert_eq!(elements, vec![1, 2, 3, 4, 5]);
+//! assert_eq!(iterator.exhausted(), Option::Some(Exhaustion::Candidates(9)));
+//! ```
+
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::Config;
+
+/// Limits on the work that goes into generating a sequence.
+///
+/// A `Budget` is built by chaining its methods. Without limits, it never
+/// runs out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    time: Option<Duration>,
+    candidates: Option<usize>,
+    heap: Option<usize>,
+}
+
+impl Budget {
+    /// Creates a `Budget` without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the wall clock time, measured from the first candidate.
+    pub fn time(mut self, time: Duration) -> Self {
+        self.time = Option::Some(time);
+        self
+    }
+
+    /// Limits the number of candidates that are examined beyond the initial
+    /// segment.
+    pub fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = Option::Some(candidates);
+        self
+    }
+
+    /// Limits the size of the heap of pending expressions, or of whatever
+    /// an engine keeps to decide whether a candidate is expressable.
+    pub fn heap(mut self, heap: usize) -> Self {
+        self.heap = Option::Some(heap);
+        self
+    }
+}
+
+/// Why a sequence ended before reaching its ceiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exhaustion {
+    /// The time ran out.
+    Time(Duration),
+    /// The given number of candidates was examined.
+    Candidates(usize),
+    /// The heap grew beyond the given size.
+    Heap(usize),
+}
+
+impl Display for Exhaustion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exhaustion::Time(time) => write!(f, "the time budget of {:?} ran out", time),
+            Exhaustion::Candidates(candidates) => {
+                write!(f, "the budget of {} candidates ran out", candidates)
+            }
+            Exhaustion::Heap(heap) => write!(f, "the heap grew beyond {} entries", heap),
+        }
+    }
+}
+
+/// A sequence whose generation is bounded by a [`Budget`].
+pub trait Budgeted {
+    /// Why the sequence ended early, if its budget ran out.
+    fn exhausted(&self) -> Option<Exhaustion>;
+}
+
+impl<B: Budgeted + ?Sized> Budgeted for Box<B> {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        (**self).exhausted()
+    }
+}
+
+/// A subsumfree sequence of numbers bounded by a [`Budget`] that exposes
+/// the [`Counters`] of its work.
+pub trait Generator: Iterator<Item = usize> + Budgeted + Instrumented {}
+
+impl<G: Iterator<Item = usize> + Budgeted + Instrumented> Generator for G {}
+
+/// The elements generated so far and why the generation ended early, if it
+/// did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    /// The generated elements.
+    pub elements: Vec<usize>,
+    /// Why the generation ended before the ceiling or the requested length.
+    pub exhausted: Option<Exhaustion>,
+}
+
+impl Generated {
+    /// Takes at most `length` elements from `generator`.
+    pub fn take<G: Generator + ?Sized>(generator: &mut G, length: usize) -> Self {
+        let elements = (&mut *generator).take(length).collect();
+        Self {
+            elements,
+            exhausted: generator.exhausted(),
+        }
+    }
+
+    /// Explains that the elements are a partial result, if they are.
+    pub fn warning(&self) -> Option<String> {
+        self.exhausted.map(|exhausted| {
+            format!(
+                "stopped early after {} numbers: {}",
+                self.elements.len(),
+                exhausted
+            )
+        })
+    }
+}
+
+/// Counters of the work done by an engine, to tell a heap that explodes
+/// from a candidate that crawls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// The candidate under scrutiny, if the engine examines numbers.
+    pub candidate: Option<usize>,
+    /// The number of candidates examined beyond the initial segment.
+    pub candidates: usize,
+    /// The number of entries kept to decide whether a candidate is
+    /// expressable. For the heap engine these are the expression streams
+    /// that are alive.
+    pub heap: usize,
+    /// The number of expression streams created so far.
+    pub streams: usize,
+    /// The number of times the smallest pending expression progressed.
+    pub progressions: usize,
+}
+
+impl Display for Counters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Option::Some(candidate) = self.candidate {
+            write!(f, "candidate {}, ", candidate)?;
+        }
+        write!(
+            f,
+            "{} candidates, heap {}, {} streams, {} progressions",
+            self.candidates, self.heap, self.streams, self.progressions
+        )
+    }
+}
+
+/// An engine that exposes the [`Counters`] of its work.
+pub trait Instrumented {
+    /// The work done so far.
+    fn counters(&self) -> Counters;
+}
+
+impl<I: Instrumented + ?Sized> Instrumented for Box<I> {
+    fn counters(&self) -> Counters {
+        (**self).counters()
+    }
+}
+
+/// Reports the [`Counters`] of an engine periodically while it generates.
+///
+/// The report is made when a new candidate is examined and at least the
+/// interval passed since the previous report. The report is shared between
+/// clones, which may be sent to other threads with their [`Config`].
+///
+/// Two `Progress` are equal when they have the same interval and are clones
+/// of each other, since reports cannot be compared.
+#[derive(Clone)]
+pub struct Progress {
+    interval: Duration,
+    report: Arc<dyn Fn(&Counters) + Send + Sync>,
+}
+
+impl Progress {
+    /// Creates a `Progress` that calls `report` every `interval`.
+    pub fn new<F>(interval: Duration, report: F) -> Self
+    where
+        F: Fn(&Counters) + Send + Sync + 'static,
+    {
+        Self {
+            interval,
+            report: Arc::new(report),
+        }
+    }
+}
+
+impl Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Progress {
+    fn eq(&self, other: &Self) -> bool {
+        self.interval == other.interval && Arc::ptr_eq(&self.report, &other.report)
+    }
+}
+
+impl Eq for Progress {}
+
+/// How many times a [`Meter`] lets a candidate be examined again before it
+/// checks the time.
+const REPEATS_PER_TIME_CHECK: usize = 1024;
+
+/// Keeps track of the work done by an engine.
+#[derive(Debug, Clone)]
+pub(crate) struct Meter {
+    budget: Budget,
+    progress: Option<Progress>,
+    started: Option<Instant>,
+    repeats: usize,
+    reported: Option<Instant>,
+    counters: Counters,
+    exhausted: Option<Exhaustion>,
+}
+
+impl Meter {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            budget: config.limits(),
+            progress: config.reporter().cloned(),
+            started: Option::None,
+            repeats: 0,
+            reported: Option::None,
+            counters: Counters::default(),
+            exhausted: Option::None,
+        }
+    }
+
+    /// Records that `candidate` is examined while the engine holds `heap`
+    /// entries, and whether the budget allows it.
+    ///
+    /// The same candidate may be examined repeatedly; it counts once, and
+    /// the time is checked every [`REPEATS_PER_TIME_CHECK`] repetitions.
+    pub(crate) fn examine(&mut self, candidate: usize, heap: usize) -> bool {
+        if self.exhausted.is_none() && self.counters.candidate == Option::Some(candidate) {
+            self.repeats += 1;
+            if self.repeats.is_multiple_of(REPEATS_PER_TIME_CHECK) && !self.in_time() {
+                return false;
+            }
+            return self.hold(heap);
+        }
+        self.admit(Option::Some(candidate), heap)
+    }
+
+    /// Records that a new candidate is examined, for engines that examine
+    /// every candidate once.
+    pub(crate) fn examine_next(&mut self, heap: usize) -> bool {
+        self.admit(Option::None, heap)
+    }
+
+    /// Records that the smallest pending expression progressed.
+    pub(crate) fn progressed(&mut self) {
+        self.counters.progressions += 1;
+    }
+
+    /// Records that an expression stream was created.
+    pub(crate) fn created(&mut self) {
+        self.counters.streams += 1;
+    }
+
+    /// The counters, with the current size of the heap.
+    pub(crate) fn counters(&self, heap: usize) -> Counters {
+        Counters {
+            heap,
+            ..self.counters
+        }
+    }
+
+    pub(crate) fn exhausted(&self) -> Option<Exhaustion> {
+        self.exhausted
+    }
+
+    /// Checks the heap against the budget.
+    fn hold(&mut self, heap: usize) -> bool {
+        if self.exhausted.is_some() {
+            return false;
+        }
+        self.counters.heap = heap;
+        if let Option::Some(limit) = self.budget.heap.filter(|limit| heap > *limit) {
+            self.exhausted = Option::Some(Exhaustion::Heap(limit));
+            return false;
+        }
+        true
+    }
+
+    /// Checks the time since the first candidate against the budget.
+    fn in_time(&mut self) -> bool {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        if let Option::Some(limit) = self.budget.time.filter(|l| started.elapsed() > *l) {
+            self.exhausted = Option::Some(Exhaustion::Time(limit));
+            return false;
+        }
+        true
+    }
+
+    /// Counts a new candidate when the budget allows it, and reports the
+    /// progress when it is due.
+    fn admit(&mut self, candidate: Option<usize>, heap: usize) -> bool {
+        if !self.hold(heap) {
+            return false;
+        }
+        let candidates = self.counters.candidates;
+        if let Option::Some(limit) = self.budget.candidates.filter(|l| candidates >= *l) {
+            self.exhausted = Option::Some(Exhaustion::Candidates(limit));
+            return false;
+        }
+        if !self.in_time() {
+            return false;
+        }
+        let started = self.started.unwrap_or_else(Instant::now);
+        self.counters.candidate = candidate;
+        self.counters.candidates += 1;
+        if let Option::Some(progress) = &self.progress {
+            let reported = self.reported.get_or_insert(started);
+            if reported.elapsed() >= progress.interval {
+                *reported = Instant::now();
+                (progress.report)(&self.counters);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{Budget, Exhaustion, Meter, Progress, REPEATS_PER_TIME_CHECK};
+    use crate::Config;
+
+    fn metered(budget: Budget) -> Meter {
+        Meter::new(&Config::new().budget(budget))
+    }
+
+    #[test]
+    fn repeated_candidates_count_once() {
+        let mut meter = metered(Budget::new().candidates(2));
+        assert!(meter.examine(4, 0));
+        assert!(meter.examine(4, 0));
+        assert!(meter.examine(5, 0));
+        assert!(!meter.examine(6, 0));
+        assert_eq!(meter.exhausted(), Option::Some(Exhaustion::Candidates(2)));
+        assert!(!meter.examine(5, 0));
+    }
+
+    #[test]
+    fn heaps_and_time_are_limited() {
+        let mut meter = metered(Budget::new().heap(10));
+        assert!(meter.examine(4, 10));
+        assert!(!meter.examine(4, 11));
+        assert_eq!(meter.exhausted(), Option::Some(Exhaustion::Heap(10)));
+
+        let limit = Duration::from_millis(100);
+        let mut meter = metered(Budget::new().time(limit));
+        assert!(meter.examine(4, 0));
+        std::thread::sleep(limit * 2);
+        assert!(!meter.examine(5, 0));
+        assert_eq!(meter.exhausted(), Option::Some(Exhaustion::Time(limit)));
+    }
+
+    #[test]
+    fn time_is_checked_for_repeated_candidates() {
+        let limit = Duration::from_millis(100);
+        let mut meter = metered(Budget::new().time(limit));
+        assert!(meter.examine(4, 0));
+        std::thread::sleep(limit * 2);
+        assert!(!(0..REPEATS_PER_TIME_CHECK).all(|_| meter.examine(4, 0)));
+        assert_eq!(meter.exhausted(), Option::Some(Exhaustion::Time(limit)));
+    }
+
+    #[test]
+    fn progress_is_reported_periodically() {
+        let reports = Arc::new(AtomicUsize::new(0));
+        let counted = reports.clone();
+        let progress = Progress::new(Duration::ZERO, move |counters| {
+            let count = counted.fetch_add(1, Ordering::Relaxed) + 1;
+            assert_eq!(counters.candidates, count);
+        });
+        let mut meter = Meter::new(&Config::new().progress(progress));
+        for candidate in 4..10 {
+            meter.examine(candidate, 0);
+            meter.examine(candidate, 0);
+        }
+        assert_eq!(reports.load(Ordering::Relaxed), 6);
+        assert_eq!(meter.counters(3).heap, 3);
+        assert_eq!(meter.counters(3).candidate, Option::Some(9));
+    }
+
+    #[test]
+    fn configs_with_progress_are_sent_to_other_threads() {
+        let progress = Progress::new(Duration::ZERO, |_| {});
+        let config = Config::new().progress(progress);
+        let counted = std::thread::spawn(move || {
+            let mut meter = Meter::new(&config);
+            meter.examine(4, 0);
+            meter.counters(0).candidates
+        });
+        assert_eq!(counted.join().unwrap(), 1);
+    }
+}
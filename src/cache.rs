@@ -0,0 +1,428 @@
+//! The `cache` module stores generated sequences on disk, so that sweeps do
+//! not recompute the same sequences over and over.
+//!
+//! A [`Cache`] is a directory with one plain text file per sequence. A
+//! sequence is identified by its initial segment, its [`Mode`], its numbers
+//! of summands and its excluded and forced numbers. The ceiling is not part
+//! of the identity: every entry records a bound below which all of its
+//! elements are known, so a request with a smaller ceiling or a shorter
+//! length is answered with a prefix of the entry, and a longer request
+//! resumes the generation from the cached elements.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::budget::Generated;
+use crate::{Config, Error, Mode, sequence};
+
+/// A directory with generated sequences and their characters.
+///
+/// ```
+/// # use sequence::{Config, Mode};
+/// # use sequence::cache::Cache;
+/// let directory = std::env::temp_dir().join(format!("sequence-doc-{}", std::process::id()));
+/// let cache = Cache::new(&directory);
+/// let config = Config::new().ceiling(20);
+/// let seq = cache.generate(&[1, 2, 3], &Mode::Distinct, &config, 5).unwrap();
+/// assert_eq!(seq.elements, vec![1, 2, 3, 4, 5]);
+/// let seq = cache.generate(&[1, 2, 3], &Mode::Distinct, &config, 10).unwrap();
+/// assert_eq!(seq.elements, vec![1, 2, 3, 4, 5, 13, 14, 15]);
+/// let prefix = cache.generate(&[1, 2, 3], &Mode::Distinct, &config.ceiling(14), 10).unwrap();
+/// assert_eq!(prefix.elements, vec![1, 2, 3, 4, 5, 13]);
+/// # std::fs::remove_dir_all(directory).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    /// Creates a `Cache` in `directory`, which is created when needed.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The directory of the cache.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Generates at most `length` elements of a subsumfree sequence, like
+    /// [`sequence`] does, reusing and extending the cached
+    /// elements.
+    ///
+    /// When the budget of the configuration runs out while extending, the
+    /// elements found so far are cached and returned with the reason.
+    ///
+    /// Fails when the initial segment or the configuration is invalid, or
+    /// when the cache can not be read or written.
+    pub fn generate(
+        &self,
+        initial: &[usize],
+        mode: &Mode,
+        config: &Config,
+        length: usize,
+    ) -> Result<Generated, Error> {
+        let key = key(initial, mode, config);
+        let mut entry = self.load(&key)?;
+        let ceiling = config.maximum();
+        let known = prefix(&entry.elements, initial.len(), ceiling, length);
+        let covered = ceiling.is_some_and(|ceiling| ceiling <= entry.bound);
+        if known.len() >= length || (covered && entry.elements.len() >= initial.len()) {
+            return Ok(Generated {
+                elements: known,
+                exhausted: Option::None,
+            });
+        }
+
+        let mut iterator = if entry.elements.len() < initial.len() {
+            sequence(initial.to_vec(), mode.clone(), config.clone())?
+        } else {
+            let config = config.clone().summands(config.counts(initial)).trusted();
+            sequence(entry.elements.clone(), mode.clone(), config)?
+        };
+        let generated = Generated::take(&mut iterator, length.max(initial.len()));
+        let elements = generated.elements;
+        let complete = elements.len() < length && generated.exhausted.is_none();
+        let bound = match (complete, ceiling, elements.last()) {
+            (true, Option::Some(ceiling), _) => ceiling,
+            (_, _, Option::Some(last)) => last + 1,
+            (_, _, Option::None) => 0,
+        };
+        let result = prefix(&elements, initial.len(), ceiling, length);
+        if elements.starts_with(initial) && elements.len() > entry.elements.len() {
+            entry.elements = elements;
+            entry.bound = entry.bound.max(bound);
+            self.store(&entry)?;
+        } else if elements.starts_with(initial) && bound > entry.bound {
+            entry.bound = bound;
+            self.store(&entry)?;
+        }
+        Ok(Generated {
+            elements: result,
+            exhausted: generated.exhausted,
+        })
+    }
+
+    /// The cached character of the first `count` elements of a sequence, if
+    /// it was stored with [`Cache::store_character`].
+    pub fn character(
+        &self,
+        initial: &[usize],
+        mode: &Mode,
+        config: &Config,
+        count: usize,
+    ) -> Result<Option<String>, Error> {
+        let entry = self.load(&key(initial, mode, config))?;
+        Ok(entry.characters.get(&count).cloned())
+    }
+
+    /// Stores the character of the first `count` elements of a sequence.
+    pub fn store_character(
+        &self,
+        initial: &[usize],
+        mode: &Mode,
+        config: &Config,
+        count: usize,
+        character: &str,
+    ) -> Result<(), Error> {
+        let mut entry = self.load(&key(initial, mode, config))?;
+        entry.characters.insert(count, character.to_string());
+        self.store(&entry)
+    }
+
+    /// The file that holds the entry with `key`.
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{:016x}.txt", fnv(key)))
+    }
+
+    /// Loads the entry with `key`, which is empty when it is missing,
+    /// unreadable or belongs to another key with the same hash.
+    fn load(&self, key: &str) -> Result<Entry, Error> {
+        match fs::read_to_string(self.path(key)) {
+            Ok(contents) => Ok(Entry::parse(&contents)
+                .filter(|entry| entry.key == key)
+                .unwrap_or_else(|| Entry::new(key))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Entry::new(key)),
+            Err(error) => Err(cache_error(&self.directory, error)),
+        }
+    }
+
+    /// Stores `entry`, replacing the previous file at once.
+    fn store(&self, entry: &Entry) -> Result<(), Error> {
+        let path = self.path(&entry.key);
+        let temporary = path.with_extension("tmp");
+        fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(&temporary, entry.to_string()))
+            .and_then(|_| fs::rename(&temporary, &path))
+            .map_err(|error| cache_error(&self.directory, error))
+    }
+}
+
+/// The elements of a generated sequence that a request asks for: the initial
+/// segment, followed by the elements below the ceiling, at most `length`.
+fn prefix(elements: &[usize], seeds: usize, ceiling: Option<usize>, length: usize) -> Vec<usize> {
+    elements
+        .iter()
+        .enumerate()
+        .take_while(|(index, element)| *index < seeds || ceiling.is_none_or(|c| **element < c))
+        .map(|(_, element)| *element)
+        .take(length)
+        .collect()
+}
+
+/// The key that identifies a sequence regardless of its ceiling.
+fn key(initial: &[usize], mode: &Mode, config: &Config) -> String {
+    let mode = match mode {
+        Mode::Distinct => "distinct".to_string(),
+        Mode::Duplicate => "duplicate".to_string(),
+        Mode::Bounded(multiplicity) => format!("bounded:{}", multiplicity),
+        Mode::Linear(coefficients) => format!("linear:{}", join(coefficients.iter())),
+        Mode::Xor => "xor".to_string(),
+        Mode::Product => "product".to_string(),
+    };
+    format!(
+        "mode={} k={} initial={} exclude={} force={}",
+        mode,
+        join(config.counts(initial).iter()),
+        join(initial.iter()),
+        join(config.excluded().iter()),
+        join(config.forced().iter()),
+    )
+}
+
+fn join<'a, I: Iterator<Item = &'a usize>>(numbers: I) -> String {
+    numbers
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// The 64 bit FNV-1a hash, which, unlike the hasher of the standard library,
+/// is stable across releases.
+fn fnv(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn cache_error(directory: &Path, error: io::Error) -> Error {
+    Error::Cache {
+        reason: format!("{}: {}", directory.display(), error),
+    }
+}
+
+/// A cached sequence: all its elements below `bound` beyond the initial
+/// segment are known.
+#[derive(Debug, PartialEq, Eq)]
+struct Entry {
+    key: String,
+    bound: usize,
+    elements: Vec<usize>,
+    characters: BTreeMap<usize, String>,
+}
+
+impl Entry {
+    fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            bound: 0,
+            elements: Vec::new(),
+            characters: BTreeMap::new(),
+        }
+    }
+
+    fn parse(contents: &str) -> Option<Self> {
+        let mut lines = contents.lines();
+        let key = lines.next()?.strip_prefix("key ")?;
+        let bound = lines.next()?.strip_prefix("bound ")?.parse().ok()?;
+        let elements = lines
+            .next()?
+            .strip_prefix("elements")?
+            .split_whitespace()
+            .map(|element| element.parse().ok())
+            .collect::<Option<Vec<usize>>>()?;
+        let mut characters = BTreeMap::new();
+        for line in lines {
+            let (count, character) = line.strip_prefix("character ")?.split_once(' ')?;
+            characters.insert(count.parse().ok()?, character.to_string());
+        }
+        Option::Some(Self {
+            key: key.to_string(),
+            bound,
+            elements,
+            characters,
+        })
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "key {}", self.key)?;
+        writeln!(f, "bound {}", self.bound)?;
+        write!(f, "elements")?;
+        for element in &self.elements {
+            write!(f, " {}", element)?;
+        }
+        writeln!(f)?;
+        for (count, character) in &self.characters {
+            writeln!(f, "character {} {}", count, character)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{Cache, Entry, key};
+    use crate::budget::{Budget, Exhaustion};
+    use crate::{Config, Mode, sequence};
+
+    fn directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("sequence-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn direct(initial: &[usize], mode: &Mode, config: &Config, length: usize) -> Vec<usize> {
+        sequence(initial.to_vec(), mode.clone(), config.clone())
+            .unwrap()
+            .take(length)
+            .collect()
+    }
+
+    #[test]
+    fn cached_sequences_equal_generated_ones() {
+        let directory = directory("equal");
+        let cache = Cache::new(&directory);
+        let requests = [
+            (20, 5),
+            (100, 10),
+            (60, 100),
+            (400, 30),
+            (400, 1000),
+            (30, 1000),
+        ];
+        let modes = [
+            (Mode::Distinct, [1, 3]),
+            (Mode::Duplicate, [1, 3]),
+            (Mode::Linear(vec![1, 2]), [1, 2]),
+            (Mode::Xor, [1, 3]),
+        ];
+        for (mode, initial) in modes {
+            for (ceiling, length) in requests {
+                let config = Config::new().ceiling(ceiling).exclude([9]);
+                assert_eq!(
+                    cache
+                        .generate(&initial, &mode, &config, length)
+                        .unwrap()
+                        .elements,
+                    direct(&initial, &mode, &config, length),
+                    "{:?} below {} with length {}",
+                    mode,
+                    ceiling,
+                    length
+                );
+            }
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn prefixes_are_served_from_the_entry() {
+        let directory = directory("prefix");
+        let cache = Cache::new(&directory);
+        let config = Config::new().ceiling(100);
+        cache
+            .generate(&[1, 3], &Mode::Duplicate, &config, 1000)
+            .unwrap();
+
+        let path = cache.path(&key(&[1, 3], &Mode::Duplicate, &config));
+        let mut entry = Entry::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(entry.bound, 100);
+        entry.elements[2] = 7;
+        fs::write(&path, entry.to_string()).unwrap();
+
+        let seq = cache.generate(&[1, 3], &Mode::Duplicate, &config.ceiling(50), 3);
+        assert_eq!(seq.unwrap().elements, vec![1, 3, 7]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn corrupt_entries_are_regenerated() {
+        let directory = directory("corrupt");
+        let cache = Cache::new(&directory);
+        let config = Config::new().ceiling(100);
+        let path = cache.path(&key(&[1, 2, 3], &Mode::Distinct, &config));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&path, "garbage").unwrap();
+
+        let seq = cache
+            .generate(&[1, 2, 3], &Mode::Distinct, &config, 10)
+            .unwrap();
+        assert_eq!(
+            seq.elements,
+            direct(&[1, 2, 3], &Mode::Distinct, &config, 10)
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn partial_results_are_resumed() {
+        let directory = directory("partial");
+        let cache = Cache::new(&directory);
+        let config = Config::new().ceiling(200);
+        let budgeted = config.clone().budget(Budget::new().candidates(40));
+        let partial = cache
+            .generate(&[1, 3], &Mode::Duplicate, &budgeted, 100)
+            .unwrap();
+        assert_eq!(partial.exhausted, Option::Some(Exhaustion::Candidates(40)));
+        assert!(partial.elements.len() < 100);
+
+        let complete = cache
+            .generate(&[1, 3], &Mode::Duplicate, &config, 100)
+            .unwrap();
+        assert_eq!(complete.exhausted, Option::None);
+        assert!(complete.elements.starts_with(&partial.elements));
+        assert_eq!(
+            complete.elements,
+            direct(&[1, 3], &Mode::Duplicate, &config, 100)
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn characters_are_stored_per_prefix() {
+        let directory = directory("character");
+        let cache = Cache::new(&directory);
+        let config = Config::new().ceiling(100);
+        cache
+            .generate(&[1, 2, 3], &Mode::Distinct, &config, 10)
+            .unwrap();
+        cache
+            .store_character(
+                &[1, 2, 3],
+                &Mode::Distinct,
+                &config,
+                10,
+                "(0, 2) 6 {} {1, 2}",
+            )
+            .unwrap();
+
+        let character = |count| cache.character(&[1, 2, 3], &Mode::Distinct, &config, count);
+        assert_eq!(
+            character(10).unwrap().as_deref(),
+            Option::Some("(0, 2) 6 {} {1, 2}")
+        );
+        assert_eq!(character(20).unwrap(), Option::None);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
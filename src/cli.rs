@@ -0,0 +1,416 @@
+//! The `cli` module contains the command line arguments that the binaries
+//! share.
+//!
+//! Besides a single job given on the command line, the binaries read batches
+//! of [`Jobs`], one per line, so that many seeds can be piped through them.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines};
+use std::iter;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use clap::{Args, Parser};
+
+use crate::budget::{Budget, Generated, Generator, Progress};
+use crate::cache::Cache;
+use crate::combinatorics::form::LinearForm;
+use crate::tools::character::{Character, determine_character_of, determine_complement_character};
+use crate::tools::complement::Complement;
+use crate::{Config, Error, Mode, sequence};
+
+/// The arguments that bound and monitor the work that goes into generating a
+/// sequence.
+#[derive(Args, Debug, Clone, Default)]
+pub struct Limits {
+    /// Stop after this many seconds and keep the elements found so far.
+    #[arg(long, value_parser = parse_seconds)]
+    pub time: Option<Duration>,
+    /// Stop after examining this many candidates.
+    #[arg(long)]
+    pub candidates: Option<usize>,
+    /// Stop when the heap of pending expressions grows beyond this size.
+    #[arg(long)]
+    pub heap: Option<usize>,
+    /// Report the work done on standard error every this many seconds.
+    #[arg(long, value_parser = parse_seconds)]
+    pub progress: Option<Duration>,
+}
+
+impl Limits {
+    /// The `Budget` selected by the arguments.
+    pub fn budget(&self) -> Budget {
+        let mut budget = Budget::new();
+        if let Option::Some(time) = self.time {
+            budget = budget.time(time);
+        }
+        if let Option::Some(candidates) = self.candidates {
+            budget = budget.candidates(candidates);
+        }
+        if let Option::Some(heap) = self.heap {
+            budget = budget.heap(heap);
+        }
+        budget
+    }
+
+    /// Applies the budget and the progress reports to `config`.
+    pub fn configure(&self, config: Config) -> Config {
+        let config = config.budget(self.budget());
+        match self.progress {
+            Option::Some(interval) => config.progress(Progress::new(interval, |counters| {
+                eprintln!("progress: {}", counters)
+            })),
+            Option::None => config,
+        }
+    }
+}
+
+/// Parses a non-negative number of seconds, e.g. `2.5`.
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .map_err(|error| error.to_string())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string()))
+}
+
+/// The arguments that determine which subsumfree sequence to generate.
+#[derive(Parser, Debug)]
+pub struct Arguments {
+    /// The maximum number of elements to generate.
+    #[arg(short, long, default_value_t = 500)]
+    pub length: usize,
+    /// The ceiling below which elements are generated.
+    #[arg(short, long, default_value_t = 1000)]
+    pub ceiling: usize,
+    /// Allow elements to occur arbitrarily often in an expression.
+    #[arg(short, long, default_value_t = false)]
+    pub duplicate: bool,
+    /// Allow elements to occur at most this often in an expression.
+    #[arg(short, long, conflicts_with = "duplicate")]
+    pub multiplicity: Option<usize>,
+    /// The numbers of summands that expressions may not have, e.g. `2,3`.
+    /// Defaults to the length of the initial segment.
+    #[arg(short, long, value_delimiter = ',')]
+    pub k: Vec<usize>,
+    /// The coefficients of a linear form whose values are avoided, e.g. `1,2`
+    /// avoids `x+2y=z`.
+    #[arg(
+        short = 'a',
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["duplicate", "multiplicity", "k"]
+    )]
+    pub coefficients: Vec<usize>,
+    /// Replace addition by XOR, i.e. addition of polynomials over GF(2).
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["duplicate", "multiplicity", "coefficients"]
+    )]
+    pub xor: bool,
+    /// Replace addition by multiplication, i.e. generate product-free
+    /// sequences.
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["duplicate", "multiplicity", "coefficients", "xor"]
+    )]
+    pub product: bool,
+    /// Numbers to keep out of the sequence, e.g. `4,7`.
+    #[arg(short, long, value_delimiter = ',')]
+    pub exclude: Vec<usize>,
+    /// Numbers to put in the sequence even when they are expressable.
+    #[arg(short, long, value_delimiter = ',')]
+    pub force: Vec<usize>,
+    /// Generate the numbers beyond the initial segment and below the ceiling
+    /// that the sequence leaves out.
+    #[arg(long, default_value_t = false)]
+    pub complement: bool,
+    /// A directory where generated sequences and their characters are
+    /// cached across runs.
+    #[arg(long)]
+    pub cache: Option<PathBuf>,
+    /// The limits on the work that goes into generating the sequence.
+    #[command(flatten)]
+    pub limits: Limits,
+    /// The initial segment of the sequence.
+    pub initial: Vec<usize>,
+}
+
+impl Arguments {
+    /// The `Mode` selected by the arguments.
+    pub fn mode(&self) -> Mode {
+        if self.xor {
+            return Mode::Xor;
+        }
+        if self.product {
+            return Mode::Product;
+        }
+        if !self.coefficients.is_empty() {
+            return Mode::Linear(self.coefficients.clone());
+        }
+        match (self.duplicate, self.multiplicity) {
+            (true, _) => Mode::Duplicate,
+            (false, Option::Some(multiplicity)) => Mode::Bounded(multiplicity),
+            (false, Option::None) => Mode::Distinct,
+        }
+    }
+
+    /// The `Config` selected by the arguments.
+    pub fn config(&self) -> Config {
+        let config = Config::new()
+            .ceiling(self.ceiling)
+            .summands(self.k.iter().cloned())
+            .exclude(self.exclude.iter().cloned())
+            .force(self.force.iter().cloned());
+        self.limits.configure(config)
+    }
+
+    /// The linear form that the selected sequence avoids, if there is a
+    /// single one.
+    pub fn form(&self) -> Option<LinearForm> {
+        let k = match self.k.as_slice() {
+            [] => self.initial.len(),
+            [k] => *k,
+            _ => return Option::None,
+        };
+        match self.mode() {
+            Mode::Distinct => Option::Some(LinearForm::distinct(k)),
+            Mode::Duplicate => LinearForm::try_new(vec![1; k]).ok(),
+            Mode::Bounded(_) | Mode::Xor | Mode::Product => Option::None,
+            Mode::Linear(coefficients) => LinearForm::try_new(coefficients).ok(),
+        }
+    }
+
+    /// Iterates over the sequence selected by the arguments, or its
+    /// complement, without limiting the length.
+    pub fn iterator(&self) -> Result<Box<dyn Generator>, Error> {
+        let iterator = sequence(self.initial.clone(), self.mode(), self.config())?;
+        if self.complement {
+            let start = self.initial.last().copied().unwrap_or(0);
+            Ok(Box::new(Complement::budgeted(
+                iterator,
+                start,
+                self.ceiling,
+            )))
+        } else {
+            Ok(iterator)
+        }
+    }
+
+    /// Generates the sequence selected by the arguments, or its complement,
+    /// through the cache when there is one. When the budget runs out, the
+    /// numbers found so far are returned with the reason.
+    pub fn generate(&self) -> Result<Generated, Error> {
+        let Option::Some(directory) = &self.cache else {
+            return self
+                .iterator()
+                .map(|mut iterator| Generated::take(&mut iterator, self.length));
+        };
+        let cache = Cache::new(directory);
+        if self.complement {
+            let seq = cache.generate(&self.initial, &self.mode(), &self.config(), usize::MAX)?;
+            let start = self.initial.last().copied().unwrap_or(0);
+            // the numbers beyond the last element are unknown when the budget ran out
+            let ceiling = match (seq.exhausted, seq.elements.last()) {
+                (Option::Some(_), Option::Some(last)) => self.ceiling.min(last + 1),
+                _ => self.ceiling,
+            };
+            Ok(Generated {
+                elements: Complement::new(seq.elements.into_iter(), start, ceiling)
+                    .take(self.length)
+                    .collect(),
+                exhausted: seq.exhausted,
+            })
+        } else {
+            cache.generate(&self.initial, &self.mode(), &self.config(), self.length)
+        }
+    }
+
+    /// The `Character` of the generated numbers, taking into account whether
+    /// they form the complement.
+    pub fn character(&self, generated: &[usize], form: LinearForm) -> Option<Character> {
+        if self.complement {
+            determine_complement_character(generated, &self.initial, form)
+        } else {
+            determine_character_of(generated, form)
+        }
+    }
+
+    /// The `Character` of the generated numbers as text, like
+    /// [`Arguments::character`], looked up in and stored to the cache when
+    /// there is one. Characters of complements are not cached.
+    ///
+    /// The text does not depend on the linear form, so sequences that avoid
+    /// no single one are described as well.
+    pub fn describe_character(&self, generated: &[usize]) -> Result<Option<String>, Error> {
+        let form = self.form().unwrap_or(LinearForm::distinct(3));
+        let cache = match &self.cache {
+            Option::Some(directory) if !self.complement => Cache::new(directory),
+            _ => return Ok(self.character(generated, form).map(|c| c.to_string())),
+        };
+        let (mode, config) = (self.mode(), self.config());
+        let count = generated.len();
+        if let Option::Some(character) = cache.character(&self.initial, &mode, &config, count)? {
+            return Ok(Option::Some(character));
+        }
+        let character = self.character(generated, form).map(|c| c.to_string());
+        if let Option::Some(character) = &character {
+            cache.store_character(&self.initial, &mode, &config, count, character)?;
+        }
+        Ok(character)
+    }
+}
+
+/// A job read from a batch: the line it came from and its parsed arguments.
+#[derive(Debug)]
+pub struct Job<P> {
+    /// The line that describes the job.
+    pub line: String,
+    /// The arguments of the job, or why they could not be parsed.
+    pub parsed: Result<P, String>,
+}
+
+/// Iterates over the jobs of a batch, one per line.
+///
+/// A line either holds the arguments as they would be given on the command
+/// line, or a JSON array with just the initial segment. Empty lines and lines
+/// starting with `#` are skipped.
+///
+/// ```
+/// # use std::io::Cursor;
+/// # use sequence::cli::{Arguments, Jobs};
+/// let batch = Cursor::new("-c 100 1 2 3\n\n[1, 2, 4]\n");
+/// let jobs: Vec<Arguments> = Jobs::new(batch).map(|job| job.unwrap().parsed.unwrap()).collect();
+/// assert_eq!(jobs[0].ceiling, 100);
+/// assert_eq!(jobs[1].initial, vec![1, 2, 4]);
+/// ```
+pub struct Jobs<P> {
+    lines: Lines<Box<dyn BufRead>>,
+    parser: PhantomData<P>,
+}
+
+impl<P: Parser> Jobs<P> {
+    /// Creates the jobs of a batch that is read from `reader`.
+    pub fn new<R>(reader: R) -> Self
+    where
+        R: BufRead + 'static,
+    {
+        let reader: Box<dyn BufRead> = Box::new(reader);
+        Self {
+            lines: reader.lines(),
+            parser: PhantomData,
+        }
+    }
+
+    /// Creates the jobs of a batch that is read from the file at `path`, or
+    /// from standard input when the path is `-`.
+    pub fn open(path: &str) -> Result<Self, io::Error> {
+        if path == "-" {
+            Ok(Self::new(BufReader::new(io::stdin())))
+        } else {
+            File::open(path).map(|file| Self::new(BufReader::new(file)))
+        }
+    }
+
+    /// Calls `job` with the index and the job of every line of the batch at
+    /// `path`, like [`Jobs::open`], and exits when the batch can not be read.
+    pub fn each<F>(path: &str, mut job: F)
+    where
+        F: FnMut(usize, Job<P>),
+    {
+        let jobs = Self::open(path).unwrap_or_else(|error| unreadable(path, error));
+        for (index, next) in jobs.enumerate() {
+            match next {
+                Ok(next) => job(index, next),
+                Err(error) => unreadable(path, error),
+            }
+        }
+    }
+
+    /// Runs `record` on the arguments of every job of the batch at `path`,
+    /// like [`Jobs::each`], and writes one line per job with the job and its
+    /// record, or why it failed. A record may come with a warning, which is
+    /// written to the standard error.
+    pub fn run<F>(path: &str, mut record: F)
+    where
+        F: FnMut(P) -> Result<(String, Option<String>), String>,
+    {
+        Self::each(path, |_, job| match job.parsed.and_then(&mut record) {
+            Ok((record, warning)) => {
+                println!("{}\t{}", job.line, record);
+                if let Option::Some(warning) = warning {
+                    eprintln!("warning: {}: {}", job.line, warning);
+                }
+            }
+            Err(error) => println!("{}\terror: {}", job.line, error),
+        })
+    }
+}
+
+/// Exits because the batch at `path` can not be read.
+fn unreadable(path: &str, error: io::Error) -> ! {
+    eprintln!("error: {}: {}", path, error);
+    process::exit(1);
+}
+
+impl<P: Parser> Iterator for Jobs<P> {
+    type Item = Result<Job<P>, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line.trim().to_string(),
+                Err(error) => return Option::Some(Err(error)),
+            };
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = P::try_parse_from(iter::once("job".to_string()).chain(tokens(&line)))
+                .map_err(|error| {
+                    let message = error.to_string();
+                    let first = message.lines().next().unwrap_or_default();
+                    first.trim_start_matches("error: ").to_string()
+                });
+            return Option::Some(Ok(Job { line, parsed }));
+        }
+        Option::None
+    }
+}
+
+/// Splits a line of a batch into arguments.
+fn tokens(line: &str) -> Vec<String> {
+    match line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+    {
+        Option::Some(array) => array
+            .split(',')
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .collect(),
+        Option::None => line.split_whitespace().map(str::to_string).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn jobs_report_unparsable_lines() {
+        let batch = Cursor::new("# seeds\n-c 30 1 2 3\n--unknown 1\n[2, 3, 10]");
+        let jobs: Vec<Job<Arguments>> = Jobs::new(batch).map(|job| job.unwrap()).collect();
+
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[0].line, "-c 30 1 2 3");
+        assert_eq!(jobs[0].parsed.as_ref().unwrap().ceiling, 30);
+        assert!(jobs[1].parsed.is_err());
+        assert_eq!(jobs[2].parsed.as_ref().unwrap().initial, vec![2, 3, 10]);
+    }
+}
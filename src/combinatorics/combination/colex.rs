@@ -0,0 +1,132 @@
+//! Combinations in colexicographic order.
+
+use crate::Error;
+
+/// An iterator for characteristic words of combinations in colexicographic
+/// order.
+///
+/// The combinations are ordered by their largest chosen element, then by
+/// their second largest, and so on. In particular, all combinations of the
+/// first m elements precede those that choose element m.
+///
+/// ```
+/// # use sequence::combinatorics::combination::Colex;
+/// let actual: Vec<Vec<usize>> = Colex::new(4, 2).collect();
+/// let expected = vec![
+///     vec![1, 1, 0, 0],
+///     vec![1, 0, 1, 0],
+///     vec![0, 1, 1, 0],
+///     vec![1, 0, 0, 1],
+///     vec![0, 1, 0, 1],
+///     vec![0, 0, 1, 1],
+/// ];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Colex {
+    started: bool,
+    indices: Vec<usize>,
+    current: Option<Vec<usize>>,
+}
+
+impl Colex {
+    /// Creates an iterator that iterates over all characteristic words of
+    /// combinations of n elements where t are chosen, in colexicographic
+    /// order.
+    ///
+    /// When more elements are chosen than are available, the iterator is
+    /// empty. Use [`Colex::try_new`] to have that reported.
+    pub fn new(n: usize, t: usize) -> Self {
+        Self::try_new(n, t).unwrap_or(Self {
+            started: false,
+            indices: vec![],
+            current: Option::None,
+        })
+    }
+
+    /// Creates an iterator that iterates over all characteristic words of
+    /// combinations of n elements where t are chosen, in colexicographic
+    /// order, failing when t exceeds n.
+    pub fn try_new(n: usize, t: usize) -> Result<Self, Error> {
+        if t > n {
+            return Err(Error::TooManyChosen { n, t });
+        }
+        let mut indices: Vec<usize> = (0..t).collect();
+        indices.push(n);
+        let mut current = vec![0; n];
+        for letter in current.iter_mut().take(t) {
+            *letter = 1;
+        }
+        Ok(Self {
+            started: false,
+            indices,
+            current: Option::Some(current),
+        })
+    }
+
+    /// Advances to the next characteristic word without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(word) => !successor(&mut self.indices, word),
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
+            }
+        }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+/// Replaces the chosen `indices`, which end with the sentinel n, and the
+/// characteristic `word` by their successors, returning whether there is one.
+fn successor(indices: &mut [usize], word: &mut [usize]) -> bool {
+    let t = indices.len() - 1;
+    match (0..t).find(|j| indices[*j] + 1 < indices[j + 1]) {
+        Option::Some(j) => {
+            for (i, index) in indices.iter_mut().enumerate().take(j) {
+                word[*index] = 0;
+                *index = i;
+            }
+            word[indices[j]] = 0;
+            indices[j] += 1;
+            for index in &indices[..=j] {
+                word[*index] = 1;
+            }
+            true
+        }
+        Option::None => false,
+    }
+}
+
+impl Iterator for Colex {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|word| word.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Colex;
+    use crate::combinatorics::Combinations;
+
+    #[test]
+    fn colex_generates_all_combinations_in_colexicographic_order() {
+        let actual: Vec<Vec<usize>> = Colex::new(6, 3).collect();
+        let mut expected: Vec<Vec<usize>> = Combinations::new(6, 3).collect();
+        expected.sort_by(|left, right| left.iter().rev().cmp(right.iter().rev()));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn colex_handles_degenerate_cases() {
+        assert_eq!(Colex::new(3, 0).collect::<Vec<_>>(), vec![vec![0, 0, 0]]);
+        assert_eq!(Colex::new(3, 3).collect::<Vec<_>>(), vec![vec![1, 1, 1]]);
+        assert_eq!(Colex::new(2, 3).count(), 0);
+    }
+}
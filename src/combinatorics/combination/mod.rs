@@ -4,7 +4,10 @@
 //! > order of selection does not matter.
 //!
 //! The `combination` module allows to iterate over all _characteristic words_
-//! of all combinations of certain number of elements.
+//! of all combinations of certain number of elements. Besides the
+//! lexicographic order of [`Combinations`], the combinations are available in
+//! colexicographic order with [`Colex`] and in revolving-door order, where
+//! consecutive combinations differ by a single swap, with [`RevolvingDoor`].
 //!
 //! ```
 //! # use sequence::combinatorics::Combinations;
@@ -13,31 +16,293 @@
 //! assert_eq!(actual, expected);
 //! ```
 
+mod colex;
+mod revolving;
 mod sequence;
 
+pub use colex::Colex;
+pub use revolving::{Change, RevolvingDoor};
 pub use sequence::Sequence;
 
+use crate::Error;
+use crate::combinatorics::{binomial, uniform_below};
+
 /// An iterator for characteristic words of combinations.
+///
+/// Besides the `Iterator` implementation, which allocates a fresh vector for
+/// every combination, [`Combinations::advance`] steps through the
+/// combinations in place.
+///
+/// The combinations can be ranked, unranked and sampled, which allows one to
+/// split the enumeration in chunks.
+///
+/// The number of combinations quickly outgrows a `usize`, so
+/// [`Combinations::len`] returns `None` when it does not fit, and
+/// `Combinations` does not implement `ExactSizeIterator`, whose length could
+/// not be trusted.
+///
+/// ```
+/// # use sequence::combinatorics::Combinations;
+/// let combinations = Combinations::new(5, 2);
+/// assert_eq!(combinations.len(), Some(10));
+/// assert_eq!(combinations.unrank(4), Some(vec![0, 1, 1, 0, 0]));
+/// assert_eq!(combinations.rank(&[0, 1, 1, 0, 0]), Some(4));
+///
+/// let chunk: Vec<Vec<usize>> = Combinations::new(5, 2).skip(8).collect();
+/// assert_eq!(chunk, vec![vec![0, 0, 1, 0, 1], vec![0, 0, 0, 1, 1]]);
+/// ```
 #[derive(Debug, PartialEq, Eq)]
 pub struct Combinations {
+    n: usize,
     t: usize,
+    started: bool,
+    position: usize,
     current: Option<Vec<usize>>,
 }
 
 impl Combinations {
     /// Creates an iterator that iterates over all characteristic words of
     /// combinations of n elements where t are chosen.
+    ///
+    /// When more elements are chosen than are available, the iterator is
+    /// empty. Use [`Combinations::try_new`] to have that reported.
     pub fn new(n: usize, t: usize) -> Self {
+        Self::try_new(n, t).unwrap_or(Self {
+            n,
+            t,
+            started: false,
+            position: 0,
+            current: Option::None,
+        })
+    }
+
+    /// Creates an iterator that iterates over all characteristic words of
+    /// combinations of n elements where t are chosen, failing when t exceeds
+    /// n.
+    ///
+    /// ```
+    /// # use sequence::Error;
+    /// # use sequence::combinatorics::Combinations;
+    /// assert!(Combinations::try_new(5, 2).is_ok());
+    /// assert_eq!(Combinations::try_new(2, 5), Err(Error::TooManyChosen { n: 2, t: 5 }));
+    /// ```
+    pub fn try_new(n: usize, t: usize) -> Result<Self, Error> {
+        if t > n {
+            return Err(Error::TooManyChosen { n, t });
+        }
         let mut current: Vec<usize> = vec![0; n];
-        if n > 0 {
-            for index in 0..t {
-                current[index] = 1;
-            }
+        for letter in current.iter_mut().take(t) {
+            *letter = 1;
         }
-        Self {
+        Ok(Self {
+            n,
             t,
+            started: false,
+            position: 0,
             current: Option::Some(current),
+        })
+    }
+
+    /// The total number of combinations, or `None` when it does not fit in a
+    /// `usize`.
+    fn total(&self) -> Option<usize> {
+        binomial(self.n, self.t)
+    }
+
+    /// Determines the position of a characteristic word in the enumeration.
+    ///
+    /// Returns `None` when the word is not a characteristic word of a
+    /// combination of n elements where t are chosen.
+    pub fn rank(&self, word: &[usize]) -> Option<usize> {
+        if word.len() != self.n
+            || word.iter().any(|letter| *letter > 1)
+            || word.iter().sum::<usize>() != self.t
+        {
+            return Option::None;
         }
+        let indices: Vec<usize> = word
+            .iter()
+            .enumerate()
+            .filter(|(_, letter)| **letter == 1)
+            .map(|(index, _)| index)
+            .collect();
+        rank_indices(self.n, &indices)
+    }
+
+    /// Determines the characteristic word at a position in the enumeration.
+    ///
+    /// Returns `None` when the position is beyond the last combination.
+    pub fn unrank(&self, rank: usize) -> Option<Vec<usize>> {
+        unrank_indices(self.n, self.t, rank).map(|indices| {
+            let mut word = vec![0; self.n];
+            for index in indices {
+                word[index] = 1;
+            }
+            word
+        })
+    }
+
+    /// The number of combinations that are still to come, or `None` when it
+    /// does not fit in a `usize`.
+    pub fn len(&self) -> Option<usize> {
+        self.total()
+            .map(|total| total.saturating_sub(self.position))
+    }
+
+    /// Whether no combinations are to come.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Option::Some(0)
+    }
+
+    /// Moves the iterator so that it continues with the combination at
+    /// position `rank`.
+    pub fn seek(&mut self, rank: usize) {
+        self.current = self.unrank(rank);
+        self.started = false;
+        self.position = rank;
+    }
+
+    /// Draws a uniformly distributed characteristic word.
+    ///
+    /// The `random` source should produce uniformly distributed 64-bit
+    /// numbers. Returns `None` when there are no combinations.
+    ///
+    /// ```
+    /// # use sequence::combinatorics::Combinations;
+    /// let mut state: u64 = 37;
+    /// let mut xorshift = || {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     state
+    /// };
+    /// let word = Combinations::new(10, 4).sample(&mut xorshift).unwrap();
+    /// assert_eq!(word.iter().sum::<usize>(), 4);
+    /// ```
+    pub fn sample<R>(&self, random: &mut R) -> Option<Vec<usize>>
+    where
+        R: FnMut() -> u64,
+    {
+        // the ranks that do not fit in a `usize` are never drawn
+        match self.total().unwrap_or(usize::MAX) {
+            0 => Option::None,
+            total => self.unrank(uniform_below(total, random)),
+        }
+    }
+
+    /// Advances to the next characteristic word without allocating.
+    ///
+    /// ```
+    /// # use sequence::combinatorics::Combinations;
+    /// let mut combinations = Combinations::new(3, 2);
+    /// let mut count = 0;
+    /// while let Some(word) = combinations.advance() {
+    ///     assert_eq!(word.iter().sum::<usize>(), 2);
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 3);
+    /// ```
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(combination) => !successor(combination, self.t),
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
+            }
+        }
+        self.started = true;
+        if self.current.is_some() {
+            self.position += 1;
+        }
+        self.current.as_deref()
+    }
+}
+
+/// Determines the position of increasing indices among the combinations of
+/// n elements, in lexicographic order.
+pub(crate) fn rank_indices(n: usize, indices: &[usize]) -> Option<usize> {
+    let t = indices.len();
+    let mut rank: usize = 0;
+    let mut next = 0;
+    for (i, index) in indices.iter().enumerate() {
+        for skipped in next..*index {
+            rank = rank.checked_add(binomial(n - 1 - skipped, t - 1 - i)?)?;
+        }
+        next = index + 1;
+    }
+    Option::Some(rank)
+}
+
+/// Determines the increasing indices at a position among the combinations of
+/// n elements where t are chosen, in lexicographic order.
+pub(crate) fn unrank_indices(n: usize, t: usize, rank: usize) -> Option<Vec<usize>> {
+    if rank >= binomial(n, t).unwrap_or(usize::MAX) {
+        return Option::None;
+    }
+    let mut rank = rank;
+    let mut indices = Vec::with_capacity(t);
+    let mut candidate = 0;
+    for i in 0..t {
+        loop {
+            match binomial(n - 1 - candidate, t - 1 - i) {
+                Option::Some(count) if rank >= count => {
+                    rank -= count;
+                    candidate += 1;
+                }
+                _ => {
+                    indices.push(candidate);
+                    candidate += 1;
+                    break;
+                }
+            }
+        }
+    }
+    Option::Some(indices)
+}
+
+/// Replaces `combination` by its successor, returning whether there is one.
+fn successor(combination: &mut [usize], t: usize) -> bool {
+    if combination.is_empty() {
+        return false;
+    }
+    let mut index = combination.len() - 1;
+    while index > 0 && combination[index] == 0 {
+        index -= 1;
+    }
+    if index == combination.len() - 1 {
+        let mut count = 0;
+        while index > 0 && combination[index] == 1 {
+            combination[index] = 0;
+            index -= 1;
+            count += 1;
+        }
+        if index == 0 && combination[index] == 1 {
+            count += 1;
+        }
+        if count < t {
+            while index > 0 && combination[index] == 0 {
+                index -= 1;
+            }
+            combination[index] = 0;
+            index += 1;
+            while count > 0 {
+                combination[index] = 1;
+                index += 1;
+                count -= 1;
+            }
+            combination[index] = 1;
+            true
+        } else {
+            false
+        }
+    } else if combination[index] == 1 {
+        combination[index] = 0;
+        combination[index + 1] = 1;
+        true
+    } else {
+        false
     }
 }
 
@@ -45,59 +310,99 @@ impl Iterator for Combinations {
     type Item = Vec<usize>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match &self.current {
-            Option::Some(current) => {
-                let mut combination: Vec<usize> = current.to_vec();
-                let result = Option::Some(combination.to_vec());
-                let mut index = combination.len() - 1;
-                while index > 0 && combination[index] == 0 {
-                    index -= 1;
-                }
-                if index == combination.len() - 1 {
-                    let mut count = 0;
-                    while index > 0 && combination[index] == 1 {
-                        combination[index] = 0;
-                        index -= 1;
-                        count += 1;
-                    }
-                    if index == 0 && combination[index] == 1 {
-                        count += 1;
-                    }
-                    if count < self.t {
-                        while index > 0 && combination[index] == 0 {
-                            index -= 1;
-                        }
-                        combination[index] = 0;
-                        index += 1;
-                        while count > 0 {
-                            combination[index] = 1;
-                            index += 1;
-                            count -= 1;
+        self.advance().map(|combination| combination.to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len() {
+            Option::Some(remaining) => (remaining, Option::Some(remaining)),
+            Option::None => (usize::MAX, Option::None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 {
+            self.seek(self.position.saturating_add(n));
+        }
+        self.next()
+    }
+}
+
+/// An iterator for combinations represented by the increasing indices of the
+/// chosen elements.
+///
+/// The combinations are produced in the same order as [`Combinations`], but
+/// the state only takes space proportional to the number of chosen elements.
+///
+/// ```
+/// # use sequence::combinatorics::combination::Indices;
+/// let actual: Vec<Vec<usize>> = Indices::new(4, 2).collect();
+/// let expected = vec![
+///     vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3], vec![2, 3],
+/// ];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Indices {
+    n: usize,
+    started: bool,
+    current: Option<Vec<usize>>,
+}
+
+impl Indices {
+    /// Creates an iterator over the indices of combinations of n elements
+    /// where t are chosen.
+    pub fn new(n: usize, t: usize) -> Self {
+        Self {
+            n,
+            started: false,
+            current: if t <= n {
+                Option::Some((0..t).collect())
+            } else {
+                Option::None
+            },
+        }
+    }
+
+    /// Advances to the next combination without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(indices) => {
+                    let t = indices.len();
+                    match (0..t).rev().find(|i| indices[*i] < self.n - t + *i) {
+                        Option::Some(i) => {
+                            indices[i] += 1;
+                            for j in (i + 1)..t {
+                                indices[j] = indices[j - 1] + 1;
+                            }
+                            false
                         }
-                        combination[index] = 1;
-                        self.current = Option::Some(combination)
-                    } else {
-                        self.current = Option::None
-                    }
-                } else {
-                    if combination[index] == 1 {
-                        combination[index] = 0;
-                        combination[index + 1] = 1;
-                        self.current = Option::Some(combination)
-                    } else {
-                        self.current = Option::None
+                        Option::None => true,
                     }
                 }
-                result
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
             }
-            Option::None => Option::None,
         }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+impl Iterator for Indices {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|indices| indices.to_vec())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Combinations;
+    use super::{Combinations, Indices};
 
     #[test]
     fn combinations_4_2_generates_all_possibilities() {
@@ -138,4 +443,84 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn indices_follow_the_order_of_combinations() {
+        let actual: Vec<Vec<usize>> = Indices::new(6, 3)
+            .map(|indices| {
+                let mut word = vec![0; 6];
+                for index in indices {
+                    word[index] = 1;
+                }
+                word
+            })
+            .collect();
+        let expected: Vec<Vec<usize>> = Combinations::new(6, 3).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn advance_visits_the_same_combinations_as_the_iterator() {
+        let mut combinations = Combinations::new(6, 3);
+        let mut actual: Vec<Vec<usize>> = Vec::new();
+        while let Option::Some(combination) = combinations.advance() {
+            actual.push(combination.to_vec());
+        }
+        let expected: Vec<Vec<usize>> = Combinations::new(6, 3).collect();
+
+        assert_eq!(actual, expected);
+        assert_eq!(combinations.advance(), Option::None);
+    }
+
+    #[test]
+    fn rank_and_unrank_follow_the_enumeration() {
+        let combinations = Combinations::new(7, 3);
+        for (rank, word) in Combinations::new(7, 3).enumerate() {
+            assert_eq!(combinations.rank(&word), Option::Some(rank));
+            assert_eq!(combinations.unrank(rank), Option::Some(word));
+        }
+        assert_eq!(combinations.unrank(35), Option::None);
+        assert_eq!(combinations.rank(&[1, 1, 0, 0, 0, 0, 0]), Option::None);
+    }
+
+    #[test]
+    fn length_and_seeking_are_consistent() {
+        let mut combinations = Combinations::new(6, 2);
+        assert_eq!(combinations.len(), Option::Some(15));
+        combinations.next();
+        assert_eq!(combinations.len(), Option::Some(14));
+        assert_eq!(combinations.nth(5), Combinations::new(6, 2).nth(6));
+        assert_eq!(combinations.size_hint(), (8, Option::Some(8)));
+        assert_eq!(combinations.count(), 8);
+
+        let mut single = Combinations::new(2, 2);
+        assert!(!single.is_empty());
+        single.next();
+        assert!(single.is_empty());
+    }
+
+    #[test]
+    fn lengths_that_overflow_are_unknown() {
+        let mut combinations = Combinations::new(200, 100);
+        assert_eq!(combinations.len(), Option::None);
+        assert!(!combinations.is_empty());
+        assert_eq!(combinations.size_hint(), (usize::MAX, Option::None));
+        combinations.next();
+        assert_eq!(combinations.size_hint(), (usize::MAX, Option::None));
+    }
+
+    #[test]
+    fn combinations_0_0_generates_the_empty_word() {
+        let actual: Vec<Vec<usize>> = Combinations::new(0, 0).collect();
+        let expected: Vec<Vec<usize>> = vec![vec![]];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn combinations_choosing_too_many_are_empty() {
+        assert_eq!(Combinations::new(2, 3).count(), 0);
+        assert!(Combinations::try_new(2, 3).is_err());
+    }
 }
@@ -0,0 +1,228 @@
+//! Combinations in revolving-door order, a Gray code for combinations.
+
+use crate::Error;
+
+/// The minimal change between consecutive combinations.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Change {
+    /// The index that entered the combination.
+    pub entered: usize,
+    /// The index that left the combination.
+    pub left: usize,
+}
+
+/// An iterator for characteristic words of combinations in revolving-door
+/// order.
+///
+/// Consecutive combinations differ by a single swap: one index leaves the
+/// combination and another enters it. The [`RevolvingDoor::change`] reports
+/// that swap, so one can update e.g. a sumset incrementally.
+///
+/// ```
+/// # use sequence::combinatorics::combination::{Change, RevolvingDoor};
+/// let mut combinations = RevolvingDoor::new(4, 2);
+/// assert_eq!(combinations.advance(), Some(&[1, 1, 0, 0][..]));
+/// assert_eq!(combinations.change(), None);
+/// assert_eq!(combinations.advance(), Some(&[0, 1, 1, 0][..]));
+/// assert_eq!(combinations.change(), Some(Change { entered: 2, left: 0 }));
+/// ```
+///
+/// The order is Knuth's Algorithm R from _The Art of Computer Programming_,
+/// section 7.2.1.3.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RevolvingDoor {
+    started: bool,
+    indices: Vec<usize>,
+    change: Option<Change>,
+    current: Option<Vec<usize>>,
+}
+
+impl RevolvingDoor {
+    /// Creates an iterator that iterates over all characteristic words of
+    /// combinations of n elements where t are chosen, in revolving-door
+    /// order.
+    ///
+    /// When more elements are chosen than are available, the iterator is
+    /// empty. Use [`RevolvingDoor::try_new`] to have that reported.
+    pub fn new(n: usize, t: usize) -> Self {
+        Self::try_new(n, t).unwrap_or(Self {
+            started: false,
+            indices: vec![],
+            change: Option::None,
+            current: Option::None,
+        })
+    }
+
+    /// Creates an iterator that iterates over all characteristic words of
+    /// combinations of n elements where t are chosen, in revolving-door
+    /// order, failing when t exceeds n.
+    pub fn try_new(n: usize, t: usize) -> Result<Self, Error> {
+        if t > n {
+            return Err(Error::TooManyChosen { n, t });
+        }
+        // The chosen indices are followed by two sentinels.
+        let mut indices: Vec<usize> = (0..t).collect();
+        indices.push(n);
+        indices.push(n + 1);
+        let mut current = vec![0; n];
+        for letter in current.iter_mut().take(t) {
+            *letter = 1;
+        }
+        Ok(Self {
+            started: false,
+            indices,
+            change: Option::None,
+            current: Option::Some(current),
+        })
+    }
+
+    /// The change that turned the previous combination into the current one.
+    ///
+    /// This is `None` before the second combination is reached.
+    pub fn change(&self) -> Option<Change> {
+        self.change
+    }
+
+    /// Advances to the next characteristic word without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started
+            && let Option::Some(word) = &mut self.current
+        {
+            match successor(&mut self.indices) {
+                Option::Some(change) => {
+                    word[change.left] = 0;
+                    word[change.entered] = 1;
+                    self.change = Option::Some(change);
+                }
+                Option::None => {
+                    self.current = Option::None;
+                    self.change = Option::None;
+                }
+            }
+        }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+/// Replaces the chosen `indices`, which are followed by two sentinels, by
+/// their successor, returning the change if there is one.
+fn successor(c: &mut [usize]) -> Option<Change> {
+    let t = c.len() - 2;
+    if t == 0 {
+        return Option::None;
+    }
+    // The steps of Algorithm R, with 0-based positions.
+    let mut j;
+    if t % 2 == 1 {
+        if c[0] + 1 < c[1] {
+            c[0] += 1;
+            return Option::Some(Change {
+                entered: c[0],
+                left: c[0] - 1,
+            });
+        }
+        j = 1;
+    } else {
+        if c[0] > 0 {
+            c[0] -= 1;
+            return Option::Some(Change {
+                entered: c[0],
+                left: c[0] + 1,
+            });
+        }
+        j = 1;
+        if let Option::Some(change) = increase(c, j) {
+            return Option::Some(change);
+        }
+        j += 1;
+    }
+    while j < t {
+        if c[j] > j {
+            let left = c[j];
+            c[j] = c[j - 1];
+            c[j - 1] = j - 1;
+            return Option::Some(Change {
+                entered: j - 1,
+                left,
+            });
+        }
+        j += 1;
+        if let Option::Some(change) = increase(c, j) {
+            return Option::Some(change);
+        }
+        j += 1;
+    }
+    Option::None
+}
+
+/// Tries to increase the index at position `j`, moving its predecessor into
+/// its place.
+fn increase(c: &mut [usize], j: usize) -> Option<Change> {
+    if c[j] + 1 < c[j + 1] {
+        let left = c[j - 1];
+        c[j - 1] = c[j];
+        c[j] += 1;
+        Option::Some(Change {
+            entered: c[j],
+            left,
+        })
+    } else {
+        Option::None
+    }
+}
+
+impl Iterator for RevolvingDoor {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|word| word.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, RevolvingDoor};
+    use crate::combinatorics::Combinations;
+
+    #[test]
+    fn revolving_door_generates_all_combinations_by_single_swaps() {
+        for n in 0..9 {
+            for t in 0..=n {
+                let mut combinations = RevolvingDoor::new(n, t);
+                let mut actual: Vec<Vec<usize>> = Vec::new();
+                while let Option::Some(word) = combinations.advance() {
+                    let word = word.to_vec();
+                    if let Option::Some(previous) = actual.last() {
+                        let Change { entered, left } = combinations.change().unwrap();
+                        let mut expected = previous.clone();
+                        expected[left] = 0;
+                        expected[entered] = 1;
+                        assert_eq!(previous[left], 1);
+                        assert_eq!(previous[entered], 0);
+                        assert_eq!(word, expected);
+                    }
+                    actual.push(word);
+                }
+                let mut expected: Vec<Vec<usize>> = Combinations::new(n, t).collect();
+                actual.sort();
+                expected.sort();
+                assert_eq!(actual, expected, "n = {}, t = {}", n, t);
+            }
+        }
+    }
+
+    #[test]
+    fn revolving_door_4_2_is_in_revolving_door_order() {
+        let actual: Vec<Vec<usize>> = RevolvingDoor::new(4, 2).collect();
+        let expected = vec![
+            vec![1, 1, 0, 0],
+            vec![0, 1, 1, 0],
+            vec![1, 0, 1, 0],
+            vec![0, 0, 1, 1],
+            vec![0, 1, 0, 1],
+            vec![1, 0, 0, 1],
+        ];
+        assert_eq!(actual, expected);
+    }
+}
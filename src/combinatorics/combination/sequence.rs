@@ -11,75 +11,39 @@
 //! assert_eq!(actual, expected);
 //! ```
 
-use crate::combinatorics::Combinations;
-use crate::tools::ItemCandidate;
-use std::collections::BinaryHeap;
-
-#[derive(Debug, Eq, PartialEq)]
-struct Data {
-    n: usize,
-    weights: Vec<usize>,
-    iterator: Combinations,
-}
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented};
+use crate::combinatorics::combination::Indices;
+use crate::combinatorics::heap::{Engine, Summands};
+use crate::combinatorics::operation::{Operation, Sum};
+use crate::{Config, Error};
 
-impl Data {
-    fn new(t: usize, elements: &[usize]) -> Self {
-        let m = elements.len();
-        let weights = elements.into_iter().cloned().collect();
-        let mut iterator = Combinations::new(m - 1, t - 1);
-
-        let n = iterator
-            .next()
-            .map(|mut word| {
-                word.push(1);
-                word.iter().zip(&weights).map(|pair| pair.0 * pair.1).sum()
-            })
-            .unwrap_or(0);
-        Self {
-            n,
-            weights,
-            iterator: iterator,
-        }
+impl Summands for Indices {
+    type Parameters = ();
+
+    fn choose(_: &(), n: usize, t: usize) -> Self {
+        Indices::new(n, t)
     }
 
-    fn progress(self) -> Option<Self> {
-        let mut iterator = self.iterator;
-        iterator
-            .next()
-            .map(|mut word| {
-                word.push(1);
-                word.iter()
-                    .zip(&self.weights)
-                    .map(|pair| pair.0 * pair.1)
-                    .sum()
-            })
-            .map(|n: usize| Data {
-                n,
-                weights: self.weights,
-                iterator,
-            })
+    fn accompany(_: &(), end: usize, t: usize) -> Vec<Self> {
+        vec![Indices::new(end, t)]
     }
-}
 
-impl Ord for Data {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.n.cmp(&self.n)
+    fn advance(&mut self) -> Option<&[usize]> {
+        Indices::advance(self)
     }
-}
 
-impl PartialOrd for Data {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Option::Some(self.cmp(other))
+    fn contains(&self, indices: &[usize]) -> bool {
+        indices.windows(2).all(|pair| pair[0] < pair[1])
+            && indices.last().is_none_or(|last| *last < self.n)
     }
 }
 
 /// Subsumfree sequences with an arbitrary initial seqeunce.
-pub struct Sequence {
-    t: usize,
-    current: ItemCandidate,
-    elements: Vec<usize>,
-    ceiling: Option<ItemCandidate>,
-    expressions: BinaryHeap<Data>,
+///
+/// The summands are added by default, but any [`Operation`] can combine them
+/// instead, see [`Sequence::with_operation`].
+pub struct Sequence<O: Operation = Sum> {
+    engine: Engine<Indices, O>,
 }
 
 impl Sequence {
@@ -92,8 +56,25 @@ impl Sequence {
     ///
     /// assert_eq!(max, Some(15));
     /// ```
+    ///
+    /// # Panics
+    /// Panics when the initial sequence is invalid, see [`Sequence::try_new`].
     pub fn new(initial: Vec<usize>) -> Self {
-        Self::initialize(initial, Option::None)
+        Self::try_new(initial).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence, failing when
+    /// the initial sequence is empty, not strictly increasing or not
+    /// subsumfree.
+    ///
+    /// ```
+    /// # use sequence::Error;
+    /// # use sequence::combinatorics::combination::Sequence;
+    /// assert!(Sequence::try_new(vec![1,2,3]).is_ok());
+    /// assert_eq!(Sequence::try_new(vec![]).err(), Some(Error::EmptyInitialSegment));
+    /// ```
+    pub fn try_new(initial: Vec<usize>) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new())
     }
 
     /// Creates a `Sequence` with an arbitrary initial sequence and a ceiling.
@@ -109,85 +90,98 @@ impl Sequence {
     ///
     /// assert_eq!(actual, expected);
     /// ```
+    ///
+    /// # Panics
+    /// Panics when the initial sequence is invalid, see [`Sequence::try_new`].
     pub fn with_maximum(initial: Vec<usize>, ceiling: usize) -> Self {
-        Self::initialize(initial, Option::Some(ItemCandidate::Element(ceiling)))
-    }
-
-    fn initialize(initial: Vec<usize>, ceiling: Option<ItemCandidate>) -> Self {
-        let elements: Vec<usize> = initial.iter().cloned().collect();
-        let n = initial.len();
-        let mut expressions: BinaryHeap<Data> = BinaryHeap::new();
-        expressions.push(Data::new(n, &initial));
-        Self {
-            t: n,
-            current: ItemCandidate::Index(0, initial),
-            elements,
-            ceiling,
-            expressions,
-        }
+        Self::try_with_maximum(initial, ceiling).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    fn unexpressable(&mut self, c: usize) -> Option<usize> {
-        self.elements.push(c);
-        let prefix: Vec<usize> = self.elements.iter().cloned().collect();
-        self.expressions.push(Data::new(self.t, &prefix));
-        self.current = self.current.next();
-        Option::Some(c)
+    /// Creates a `Sequence` with an arbitrary initial sequence and a ceiling,
+    /// failing when the initial sequence is invalid.
+    pub fn try_with_maximum(initial: Vec<usize>, ceiling: usize) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new().ceiling(ceiling))
     }
 
-    fn expressable(&mut self) {
-        self.progress_minimum_expression();
-        self.current = self.current.next();
+    /// Creates a `Sequence` with an arbitrary initial sequence and a
+    /// [`Config`].
+    ///
+    /// # Panics
+    /// Panics when the initial sequence or the configuration is invalid, see
+    /// [`Sequence::try_with_config`].
+    pub fn with_config(initial: Vec<usize>, config: Config) -> Self {
+        Self::try_with_config(initial, config).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    fn progress_minimum_expression(&mut self) {
-        let data = self.expressions.pop().unwrap(/* safe because we peeked */);
-        if let Option::Some(next) = data.progress() {
-            self.expressions.push(next);
-        }
+    /// Creates a `Sequence` with an arbitrary initial sequence and a
+    /// [`Config`], failing when the initial sequence is invalid or when the
+    /// configuration asks for zero summands.
+    pub fn try_with_config(initial: Vec<usize>, config: Config) -> Result<Self, Error> {
+        Self::try_with_operation(initial, Sum, config)
     }
 }
 
-impl Iterator for Sequence {
+impl<O: Operation> Sequence<O> {
+    /// Creates a `Sequence` with an arbitrary initial sequence, a
+    /// [`Config`] and an [`Operation`] that combines the summands.
+    ///
+    /// The following code demonstrates a product-free sequence.
+    ///
+    /// ```
+    /// # use sequence::Config;
+    /// # use sequence::combinatorics::Product;
+    /// # use sequence::combinatorics::combination::Sequence;
+    /// let config = Config::new().ceiling(30);
+    /// let actual: Vec<usize> = Sequence::with_operation(vec![2, 3], Product, config).collect();
+    /// let expected: Vec<usize> = vec![2, 3, 4, 5, 7, 9, 11, 13, 16, 17, 19, 23, 24, 25, 29];
+    /// assert_eq!(actual, expected);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics when the initial sequence or the configuration is invalid, see
+    /// [`Sequence::try_with_config`].
+    pub fn with_operation(initial: Vec<usize>, operation: O, config: Config) -> Self {
+        Self::try_with_operation(initial, operation, config)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence, a
+    /// [`Config`] and an [`Operation`] that combines the summands, failing
+    /// when the initial sequence is invalid or when the configuration asks
+    /// for zero summands.
+    pub fn try_with_operation(
+        initial: Vec<usize>,
+        operation: O,
+        config: Config,
+    ) -> Result<Self, Error> {
+        Engine::initialize((), operation, initial, config).map(|engine| Self { engine })
+    }
+}
+
+impl<O: Operation> Iterator for Sequence<O> {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut result = Option::None;
-        while result.is_none()
-            && self
-                .ceiling
-                .as_ref()
-                .map(|max| self.current < *max)
-                .unwrap_or(true)
-        {
-            match &self.current {
-                ItemCandidate::Index(index, initial) => {
-                    result = Option::Some(initial[*index]);
-                    self.current = self.current.next();
-                }
-                ItemCandidate::Element(c) => match self.expressions.peek() {
-                    Option::Some(peek) => {
-                        if *c < peek.n {
-                            result = self.unexpressable(*c);
-                        } else if *c == peek.n {
-                            self.expressable();
-                        } else {
-                            self.progress_minimum_expression();
-                        }
-                    }
-                    Option::None => {
-                        result = self.unexpressable(*c);
-                    }
-                },
-            }
-        }
-        result
+        self.engine.next()
+    }
+}
+
+impl<O: Operation> Budgeted for Sequence<O> {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.engine.exhausted()
+    }
+}
+
+impl<O: Operation> Instrumented for Sequence<O> {
+    fn counters(&self) -> Counters {
+        self.engine.counters()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combinatorics::operation::{Lcm, Operation, Product, SumOfSquares};
 
     #[test]
     fn sequence_computes_correct_elements() {
@@ -197,6 +191,28 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn sequence_finds_expressions_out_of_index_order() {
+        // in the order of the indices, 3 + 5 + 15 = 23 comes after 1 + 14 + 15 = 30
+        let actual: Vec<usize> = Sequence::with_maximum(vec![1, 2, 3], 40).collect();
+        let expected: Vec<usize> = vec![1, 2, 3, 4, 5, 13, 14, 15, 25, 26, 27, 37, 38];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn counters_track_the_work() {
+        let mut sequence = Sequence::with_maximum(vec![1, 2, 3], 20);
+        let elements: Vec<usize> = sequence.by_ref().collect();
+        let counters = sequence.counters();
+
+        assert_eq!(elements, vec![1, 2, 3, 4, 5, 13, 14, 15]);
+        assert_eq!(counters.candidate, Option::Some(19));
+        assert_eq!(counters.candidates, 16);
+        assert!(counters.heap <= counters.streams);
+        assert!(counters.progressions > 0);
+    }
+
     #[test]
     fn sequence_with_maximum_computes_correct_elements() {
         let actual: Vec<usize> = Sequence::with_maximum(vec![1, 3, 5], 20).take(10).collect();
@@ -204,4 +220,121 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn sequence_rejects_invalid_initial_segments() {
+        assert_eq!(
+            Sequence::try_new(vec![]).err(),
+            Some(Error::EmptyInitialSegment)
+        );
+        assert!(matches!(
+            Sequence::try_new(vec![3, 1, 5]),
+            Err(Error::Unsorted { .. })
+        ));
+        assert!(matches!(
+            Sequence::try_new(vec![1, 3, 3]),
+            Err(Error::Duplicated { .. })
+        ));
+    }
+
+    #[test]
+    fn sequence_avoids_expressions_with_any_number_of_summands() {
+        let config = Config::new().ceiling(20).summands([2, 3]);
+        let actual: Vec<usize> = Sequence::with_config(vec![1, 2], config).collect();
+        let expected: Vec<usize> = vec![1, 2, 4, 8, 15];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_rejects_zero_summands() {
+        assert_eq!(
+            Sequence::try_with_config(vec![1, 2], Config::new().summands([0, 2])).err(),
+            Some(Error::ZeroSummands)
+        );
+    }
+
+    #[test]
+    fn sequence_avoids_other_operations() {
+        let config = Config::new().ceiling(60).summands([2]);
+        let actual: Vec<usize> =
+            Sequence::with_operation(vec![1, 2], SumOfSquares, config).collect();
+        let expected: Vec<usize> = vec![
+            1, 2, 3, 4, 6, 7, 8, 9, 11, 12, 14, 15, 16, 18, 19, 21, 22, 23, 24, 26, 27, 28, 29, 30,
+            31, 32, 33, 34, 35, 36, 38, 39, 41, 42, 43, 44, 46, 47, 48, 49, 51, 54, 55, 56, 57, 59,
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lcm_free_sequence_agrees_with_brute_force() {
+        for k in [2, 3] {
+            let initial = vec![2, 3, 5, 7];
+            let mut expected = initial.clone();
+            for c in 8..100 {
+                let mut selections = Indices::new(expected.len(), k);
+                if !selections
+                    .any(|indices| Lcm.combine(indices.iter().map(|index| expected[*index])) == c)
+                {
+                    expected.push(c);
+                }
+            }
+            let config = Config::new().ceiling(100).summands([k]);
+            let actual: Vec<usize> = Sequence::with_operation(initial, Lcm, config).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn product_free_sequence_rejects_products_in_the_seed() {
+        assert_eq!(
+            Sequence::try_with_operation(vec![2, 3, 6], Product, Config::new().summands([2])).err(),
+            Some(Error::Expressable {
+                index: 2,
+                element: 6
+            })
+        );
+    }
+
+    #[test]
+    fn sequence_respects_forced_elements() {
+        let config = Config::new().ceiling(25).force([6, 10]);
+        let actual: Vec<usize> = Sequence::with_config(vec![1, 2, 3], config).collect();
+        let expected: Vec<usize> = vec![1, 2, 3, 4, 5, 6, 10, 22, 23, 24];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_rejects_elements_that_are_excluded_and_forced() {
+        let config = Config::new().exclude([4, 7]).force([7]);
+        assert_eq!(
+            Sequence::try_with_config(vec![1, 2, 3], config).err(),
+            Some(Error::ExcludedAndForced { element: 7 })
+        );
+    }
+
+    #[test]
+    fn sequence_rejects_excluded_seeds_and_forced_numbers_within_the_seed() {
+        let config = Config::new().exclude([2]);
+        assert_eq!(
+            Sequence::try_with_config(vec![1, 2], config).err(),
+            Some(Error::ExcludedInitial {
+                index: 1,
+                element: 2
+            })
+        );
+        for forced in [1, 2, 3] {
+            let config = Config::new().force([forced, 10]);
+            assert_eq!(
+                Sequence::try_with_config(vec![1, 2, 3], config).err(),
+                Some(Error::ForcedInitial {
+                    element: forced,
+                    last: 3
+                })
+            );
+        }
+    }
 }
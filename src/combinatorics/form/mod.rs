@@ -0,0 +1,218 @@
+//! The `form` module generalizes the sums of the subsumfree sequences to
+//! linear forms `a₁x₁ + … + aₖxₖ` with positive coefficients.
+//!
+//! ## Example
+//! The following code enumerates the assignments of two variables whose
+//! largest index is 1.
+//!
+//! ```
+//! # use sequence::combinatorics::form::Assignments;
+//! let mut actual: Vec<Vec<usize>> = Assignments::with_largest(2, 1, 0).collect();
+//! actual.extend(Assignments::with_largest(2, 1, 1));
+//! let expected = vec![vec![1, 0], vec![1, 1], vec![0, 1]];
+//! assert_eq!(actual, expected);
+//! ```
+
+mod sequence;
+
+pub use sequence::Sequence;
+
+use crate::Error;
+
+/// A linear form `a₁x₁ + … + aₖxₖ` with positive coefficients.
+///
+/// The variables either range freely over the elements of a sequence, or are
+/// distinct elements as in the subsumfree sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearForm {
+    coefficients: Vec<usize>,
+    distinct: bool,
+}
+
+impl LinearForm {
+    /// Creates a linear form with the given coefficients, where the variables
+    /// range freely.
+    ///
+    /// # Panics
+    /// Panics when the coefficients are invalid, see [`LinearForm::try_new`].
+    pub fn new(coefficients: Vec<usize>) -> Self {
+        Self::try_new(coefficients).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a linear form with the given coefficients, failing when there
+    /// are no coefficients or when a coefficient is zero.
+    ///
+    /// ```
+    /// # use sequence::Error;
+    /// # use sequence::combinatorics::form::LinearForm;
+    /// assert!(LinearForm::try_new(vec![1, 2]).is_ok());
+    /// assert_eq!(LinearForm::try_new(vec![]).err(), Some(Error::ZeroSummands));
+    /// assert_eq!(LinearForm::try_new(vec![1, 0]).err(), Some(Error::ZeroCoefficient));
+    /// ```
+    pub fn try_new(coefficients: Vec<usize>) -> Result<Self, Error> {
+        if coefficients.is_empty() {
+            return Err(Error::ZeroSummands);
+        }
+        if coefficients.contains(&0) {
+            return Err(Error::ZeroCoefficient);
+        }
+        Ok(Self {
+            coefficients,
+            distinct: false,
+        })
+    }
+
+    /// The sum of `k` distinct variables, i.e. the form that the subsumfree
+    /// sequences avoid.
+    pub fn distinct(k: usize) -> Self {
+        Self {
+            coefficients: vec![1; k],
+            distinct: true,
+        }
+    }
+
+    /// The coefficients of the form.
+    pub fn coefficients(&self) -> &[usize] {
+        &self.coefficients
+    }
+
+    /// Whether the variables must be distinct.
+    pub fn is_distinct(&self) -> bool {
+        self.distinct
+    }
+}
+
+/// Iterates over assignments of variables to indices in lexicographic order.
+///
+/// Every variable ranges over its own interval of indices, so the state only
+/// takes space proportional to the number of variables.
+///
+/// ```
+/// # use sequence::combinatorics::form::Assignments;
+/// let actual: Vec<Vec<usize>> = Assignments::new(2, 2).collect();
+/// let expected = vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1]];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Assignments {
+    lower: Vec<usize>,
+    upper: Vec<usize>,
+    started: bool,
+    current: Option<Vec<usize>>,
+}
+
+impl Assignments {
+    /// Creates an iterator over the assignments of `k` variables to indices
+    /// below `n`.
+    pub fn new(k: usize, n: usize) -> Self {
+        Self::between(vec![0; k], vec![n; k])
+    }
+
+    /// Creates an iterator over the assignments of `k` variables to indices
+    /// of at most `largest`, where the variable at `position` is the first
+    /// one assigned to `largest`.
+    ///
+    /// Together the positions cover every assignment whose largest index is
+    /// `largest` exactly once.
+    pub fn with_largest(k: usize, largest: usize, position: usize) -> Self {
+        let lower = (0..k)
+            .map(|variable| if variable == position { largest } else { 0 })
+            .collect();
+        let upper = (0..k)
+            .map(|variable| {
+                if variable < position {
+                    largest
+                } else {
+                    largest + 1
+                }
+            })
+            .collect();
+        Self::between(lower, upper)
+    }
+
+    fn between(lower: Vec<usize>, upper: Vec<usize>) -> Self {
+        let empty = lower.iter().zip(&upper).any(|(l, u)| l >= u);
+        Self {
+            current: if empty {
+                Option::None
+            } else {
+                Option::Some(lower.clone())
+            },
+            lower,
+            upper,
+            started: false,
+        }
+    }
+
+    /// Whether `indices` is one of the assignments.
+    pub fn contains(&self, indices: &[usize]) -> bool {
+        indices.len() == self.lower.len()
+            && indices
+                .iter()
+                .zip(self.lower.iter().zip(&self.upper))
+                .all(|(index, (lower, upper))| lower <= index && index < upper)
+    }
+
+    /// Advances to the next assignment without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(indices) => {
+                    match (0..indices.len())
+                        .rev()
+                        .find(|i| indices[*i] + 1 < self.upper[*i])
+                    {
+                        Option::Some(i) => {
+                            indices[i] += 1;
+                            indices[(i + 1)..].copy_from_slice(&self.lower[(i + 1)..]);
+                            false
+                        }
+                        Option::None => true,
+                    }
+                }
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
+            }
+        }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+impl Iterator for Assignments {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|indices| indices.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Assignments;
+
+    #[test]
+    fn assignments_with_largest_partition_the_assignments() {
+        for k in 1..4 {
+            for largest in 0..4 {
+                let mut actual: Vec<Vec<usize>> = (0..k)
+                    .flat_map(|position| Assignments::with_largest(k, largest, position))
+                    .collect();
+                actual.sort();
+                let expected: Vec<Vec<usize>> = Assignments::new(k, largest + 1)
+                    .filter(|indices| indices.contains(&largest))
+                    .collect();
+
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn assignments_without_indices_are_empty() {
+        assert_eq!(Assignments::new(2, 0).count(), 0);
+        assert_eq!(Assignments::new(0, 3).count(), 1);
+    }
+}
@@ -0,0 +1,240 @@
+//! This module provides a sequence that avoids the solutions of a linear
+//! equation `a₁x₁ + … + aₖxₖ = z`.
+//!
+//! ## Example
+//! The following code demonstrates the sequence without solutions of
+//! `x + 2y = z`.
+//!
+//! ```
+//! # use sequence::combinatorics::form::Sequence;
+//! let actual: Vec<usize> = Sequence::new(vec![1], vec![1, 2]).take(8).collect();
+//! let expected: Vec<usize> = vec![1, 2, 7, 8, 13, 14, 19, 20];
+//! assert_eq!(actual, expected);
+//! ```
+
+use std::rc::Rc;
+
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented};
+use crate::combinatorics::form::{Assignments, LinearForm};
+use crate::combinatorics::heap::{Engine, Summands};
+use crate::combinatorics::operation::{Operation, Sum};
+use crate::{Config, Error};
+
+impl Summands for Assignments {
+    type Parameters = Rc<[usize]>;
+
+    fn choose(coefficients: &Rc<[usize]>, n: usize, _: usize) -> Self {
+        Assignments::new(coefficients.len(), n)
+    }
+
+    fn accompany(coefficients: &Rc<[usize]>, end: usize, _: usize) -> Vec<Self> {
+        (0..coefficients.len())
+            .map(|position| Assignments::with_largest(coefficients.len(), end, position))
+            .collect()
+    }
+
+    fn advance(&mut self) -> Option<&[usize]> {
+        Assignments::advance(self)
+    }
+
+    fn contains(&self, indices: &[usize]) -> bool {
+        Assignments::contains(self, indices)
+    }
+
+    fn bound<O: Operation>(
+        coefficients: &Rc<[usize]>,
+        operation: &O,
+        indices: &[usize],
+        elements: &[usize],
+        end: Option<usize>,
+    ) -> usize {
+        Self::evaluate(coefficients, operation, indices, elements, end)
+    }
+
+    fn evaluate<O: Operation>(
+        coefficients: &Rc<[usize]>,
+        _: &O,
+        indices: &[usize],
+        elements: &[usize],
+        _: Option<usize>,
+    ) -> usize {
+        coefficients
+            .iter()
+            .zip(indices)
+            .map(|(coefficient, index)| coefficient * elements[*index])
+            .sum()
+    }
+}
+
+/// Sequences without solutions of `a₁x₁ + … + aₖxₖ = z`, where the variables
+/// range freely over the elements.
+///
+/// With all coefficients equal to 1 this is the same sequence as
+/// [`word::Sequence`](crate::combinatorics::word::Sequence) with `k` summands.
+pub struct Sequence {
+    engine: Engine<Assignments, Sum>,
+}
+
+impl Sequence {
+    /// Creates a `Sequence` with an initial segment avoiding the linear form
+    /// with `coefficients`.
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the coefficients are invalid, see
+    /// [`Sequence::try_new`].
+    pub fn new(initial: Vec<usize>, coefficients: Vec<usize>) -> Self {
+        Self::try_new(initial, coefficients).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an initial segment avoiding the linear form
+    /// with `coefficients`, failing when the coefficients are invalid, see
+    /// [`LinearForm::try_new`], or when the initial segment is empty, not
+    /// strictly increasing or contains a solution.
+    pub fn try_new(initial: Vec<usize>, coefficients: Vec<usize>) -> Result<Self, Error> {
+        Self::try_with_config(initial, coefficients, Config::new())
+    }
+
+    /// Creates a `Sequence` with an initial segment and a ceiling avoiding the
+    /// linear form with `coefficients`.
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the coefficients are invalid, see
+    /// [`Sequence::try_new`].
+    pub fn with_maximum(initial: Vec<usize>, coefficients: Vec<usize>, ceiling: usize) -> Self {
+        Self::try_with_maximum(initial, coefficients, ceiling)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an initial segment and a ceiling avoiding the
+    /// linear form with `coefficients`, failing when the initial segment or
+    /// the coefficients are invalid.
+    pub fn try_with_maximum(
+        initial: Vec<usize>,
+        coefficients: Vec<usize>,
+        ceiling: usize,
+    ) -> Result<Self, Error> {
+        Self::try_with_config(initial, coefficients, Config::new().ceiling(ceiling))
+    }
+
+    /// Creates a `Sequence` with an initial segment and a [`Config`] avoiding
+    /// the linear form with `coefficients`.
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the coefficients are invalid, see
+    /// [`Sequence::try_new`].
+    pub fn with_config(initial: Vec<usize>, coefficients: Vec<usize>, config: Config) -> Self {
+        Self::try_with_config(initial, coefficients, config)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an initial segment and a [`Config`] avoiding
+    /// the linear form with `coefficients`, failing when the initial segment
+    /// or the coefficients are invalid.
+    ///
+    /// The coefficients fix the number of summands, so the numbers of
+    /// summands of the configuration are ignored.
+    pub fn try_with_config(
+        initial: Vec<usize>,
+        coefficients: Vec<usize>,
+        config: Config,
+    ) -> Result<Self, Error> {
+        let form = LinearForm::try_new(coefficients)?;
+        let config = config.summands([form.coefficients().len()]);
+        Engine::initialize(Rc::from(form.coefficients()), Sum, initial, config)
+            .map(|engine| Self { engine })
+    }
+}
+
+impl Iterator for Sequence {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.engine.next()
+    }
+}
+
+impl Budgeted for Sequence {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.engine.exhausted()
+    }
+}
+
+impl Instrumented for Sequence {
+    fn counters(&self) -> Counters {
+        self.engine.counters()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinatorics::word::Sequence as WordSequence;
+
+    #[test]
+    fn sequence_avoids_solutions_of_the_linear_equation() {
+        let actual: Vec<usize> = Sequence::with_maximum(vec![1], vec![2, 3], 50).collect();
+        let expected: Vec<usize> = vec![
+            1, 2, 3, 4, 6, 19, 23, 25, 27, 28, 29, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 42, 43,
+            45, 46, 48,
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_with_unit_coefficients_is_the_word_sequence() {
+        for initial in [vec![1, 3], vec![2, 3], vec![3, 5]] {
+            let actual: Vec<usize> =
+                Sequence::with_maximum(initial.clone(), vec![1, 1], 500).collect();
+            let expected: Vec<usize> = WordSequence::with_maximum(initial, 500).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    /// Whether `c` is a value of the linear form with `coefficients` at the
+    /// `elements`.
+    fn is_value(elements: &[usize], coefficients: &[usize], c: usize) -> bool {
+        match coefficients.split_first() {
+            Option::None => c == 0,
+            Option::Some((coefficient, rest)) => elements
+                .iter()
+                .take_while(|element| coefficient * *element <= c)
+                .any(|element| is_value(elements, rest, c - coefficient * element)),
+        }
+    }
+
+    #[test]
+    fn sequence_agrees_with_brute_force() {
+        for (initial, coefficients) in [
+            (vec![1, 2], vec![1, 1, 3]),
+            (vec![1], vec![1, 2, 2]),
+            (vec![2, 3], vec![3, 1, 2]),
+        ] {
+            let mut expected = initial.clone();
+            for c in (initial[initial.len() - 1] + 1)..300 {
+                if !is_value(&expected, &coefficients, c) {
+                    expected.push(c);
+                }
+            }
+            let actual: Vec<usize> = Sequence::with_maximum(initial, coefficients, 300).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn sequence_rejects_invalid_coefficients_and_seeds() {
+        assert_eq!(
+            Sequence::try_new(vec![1], vec![1, 0]).err(),
+            Some(Error::ZeroCoefficient)
+        );
+        assert_eq!(
+            Sequence::try_new(vec![1, 3], vec![1, 2]).err(),
+            Some(Error::Expressable {
+                index: 1,
+                element: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! The heap engine that drives the subsumfree sequences.
+//!
+//! Every element of the sequence owns a stream of the expressions in which it
+//! is the largest summand. The streams are merged in a `BinaryHeap` so that the
+//! smallest pending expression can be compared with the current candidate.
+//!
+//! The summands are combined by an [`Operation`], addition by default. Since
+//! the streams are merged by their smallest pending value, every stream must
+//! produce its values in increasing order. An enumeration in the order of
+//! the indices does not: for three summands `1 + 2 + 10` comes before
+//! `1 + 3 + 5`. So every stream searches its selections best first, starting
+//! from the smallest one and moving one summand at a time to the next larger
+//! element, guided by the [`Operation::bound`] of the selections.
+//!
+//! The streams do not copy the elements. Instead they refer to an index into
+//! the single growing store of elements that the engine owns, and they keep
+//! the frontier of their search as lists of indices.
+
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented, Meter};
+use crate::combinatorics::operation::Operation;
+use crate::tools::{ItemCandidate, validate};
+use crate::{Config, Error};
+use std::collections::BinaryHeap;
+use std::collections::binary_heap::PeekMut;
+
+/// A compact enumeration of the other summands of an expression.
+pub(crate) trait Summands: Sized {
+    /// The parameters that all enumerations of an engine share.
+    type Parameters: Clone;
+
+    /// Enumerates the selections of `t` summands out of the first `n` elements.
+    fn choose(parameters: &Self::Parameters, n: usize, t: usize) -> Self;
+
+    /// Enumerates the selections of `t` summands that can accompany the
+    /// element at index `end`.
+    ///
+    /// The selections are split over several enumerations so that each of
+    /// them can be merged separately.
+    fn accompany(parameters: &Self::Parameters, end: usize, t: usize) -> Vec<Self>;
+
+    /// Advances to the next selection, returning the indices of the summands.
+    ///
+    /// The first selection must be the smallest one, i.e. the only one in
+    /// which no index can be decreased.
+    fn advance(&mut self) -> Option<&[usize]>;
+
+    /// Whether `indices` is one of the selections of the enumeration.
+    fn contains(&self, indices: &[usize]) -> bool;
+
+    /// The value of the expression with the summands at `indices`, together
+    /// with the element at `end` when the summands accompany it.
+    fn evaluate<O: Operation>(
+        _parameters: &Self::Parameters,
+        operation: &O,
+        indices: &[usize],
+        elements: &[usize],
+        end: Option<usize>,
+    ) -> usize {
+        operation.combine(
+            indices
+                .iter()
+                .chain(end.as_ref())
+                .map(|index| elements[*index]),
+        )
+    }
+
+    /// A lower bound of the value of the expression, see [`Operation::bound`].
+    fn bound<O: Operation>(
+        _parameters: &Self::Parameters,
+        operation: &O,
+        indices: &[usize],
+        elements: &[usize],
+        end: Option<usize>,
+    ) -> usize {
+        operation.bound(
+            indices
+                .iter()
+                .chain(end.as_ref())
+                .map(|index| elements[*index]),
+        )
+    }
+
+    /// The selections whose parent is `indices`, except the one that
+    /// increases the last index, where the parent of a selection decreases
+    /// its last index that can be decreased.
+    ///
+    /// Every selection but the smallest has exactly one parent, so the
+    /// selections form a tree that the best first search visits once. Its
+    /// paths that only increase the last index are searched in place.
+    fn branches(&self, indices: &mut [usize]) -> Vec<Vec<usize>> {
+        let mut branches = Vec::new();
+        for position in 0..indices.len().saturating_sub(1) {
+            indices[position] += 1;
+            if self.contains(indices)
+                && !((position + 1)..indices.len()).any(|q| decreasable(self, indices, q))
+            {
+                branches.push(indices.to_vec());
+            }
+            indices[position] -= 1;
+        }
+        branches
+    }
+}
+
+/// Whether the index at `position` of `selection` can be decreased within the
+/// enumeration `summands`.
+fn decreasable<S: Summands>(summands: &S, selection: &mut [usize], position: usize) -> bool {
+    if selection[position] == 0 {
+        return false;
+    }
+    selection[position] -= 1;
+    let contained = summands.contains(selection);
+    selection[position] += 1;
+    contained
+}
+
+/// A pending entry of the search of a stream: the value of an expression, or
+/// a selection whose value is at least the key.
+///
+/// The entries are ordered by their key only, the smallest first.
+#[derive(Debug)]
+struct Pending {
+    key: usize,
+    selection: Option<Vec<usize>>,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Pending {}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Option::Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug)]
+struct Data<S> {
+    n: usize,
+    end: usize,
+    summands: S,
+    pending: BinaryHeap<Pending>,
+}
+
+impl<S: Summands> Data<S> {
+    fn new<O: Operation>(
+        parameters: &S::Parameters,
+        operation: &O,
+        end: usize,
+        mut summands: S,
+        elements: &[usize],
+    ) -> Option<Self> {
+        let first = summands.advance()?.to_vec();
+        let bound = S::bound(parameters, operation, &first, elements, Option::Some(end));
+        let mut data = Self {
+            n: 0,
+            end,
+            summands,
+            pending: BinaryHeap::from([Pending {
+                key: bound,
+                selection: Option::Some(first),
+            }]),
+        };
+        if data.progress(parameters, operation, elements) {
+            Option::Some(data)
+        } else {
+            Option::None
+        }
+    }
+
+    /// Advances to the next value of the stream, which is the smallest
+    /// pending value once no pending selection can be smaller.
+    fn progress<O: Operation>(
+        &mut self,
+        parameters: &S::Parameters,
+        operation: &O,
+        elements: &[usize],
+    ) -> bool {
+        let end = Option::Some(self.end);
+        loop {
+            let Option::Some(mut top) = self.pending.peek_mut() else {
+                return false;
+            };
+            let Pending { key, selection } = &mut *top;
+            let Option::Some(indices) = selection else {
+                self.n = *key;
+                PeekMut::pop(top);
+                return true;
+            };
+            let bound = *key;
+            let branches = self.summands.branches(indices);
+            let value = S::evaluate(parameters, operation, indices, elements, end);
+            // the selection moves on to its child that increases the last index
+            let moved = indices.last_mut().map(|last| *last += 1).is_some()
+                && self.summands.contains(indices);
+            if moved {
+                *key = S::bound(parameters, operation, indices, elements, end);
+                drop(top);
+            } else {
+                PeekMut::pop(top);
+            }
+            for branch in branches {
+                self.pending.push(Pending {
+                    key: S::bound(parameters, operation, &branch, elements, end),
+                    selection: Option::Some(branch),
+                });
+            }
+            if value == bound {
+                // the bound is exact, so no pending value can be smaller
+                self.n = value;
+                return true;
+            }
+            self.pending.push(Pending {
+                key: value,
+                selection: Option::None,
+            });
+        }
+    }
+}
+
+impl<S> PartialEq for Data<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n
+    }
+}
+
+impl<S> Eq for Data<S> {}
+
+impl<S> Ord for Data<S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.n.cmp(&self.n)
+    }
+}
+
+impl<S> PartialOrd for Data<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Option::Some(self.cmp(other))
+    }
+}
+
+/// The engine shared by the subsumfree sequences.
+pub(crate) struct Engine<S: Summands, O: Operation> {
+    parameters: S::Parameters,
+    operation: O,
+    counts: Vec<usize>,
+    current: ItemCandidate,
+    elements: Vec<usize>,
+    ceiling: Option<ItemCandidate>,
+    config: Config,
+    expressions: BinaryHeap<Data<S>>,
+    meter: Meter,
+}
+
+impl<S: Summands, O: Operation> Engine<S, O> {
+    pub(crate) fn initialize(
+        parameters: S::Parameters,
+        operation: O,
+        initial: Vec<usize>,
+        config: Config,
+    ) -> Result<Self, Error> {
+        if initial.is_empty() {
+            return Err(Error::EmptyInitialSegment);
+        }
+        config.verify(&initial)?;
+        let counts = config.counts(&initial);
+        if !config.is_trusted() {
+            validate(&initial, |prefix, element| {
+                counts
+                    .iter()
+                    .any(|t| is_sum::<S, O>(&parameters, &operation, prefix, *t, element))
+            })?;
+        }
+        let elements: Vec<usize> = initial.to_vec();
+        let mut engine = Self {
+            parameters,
+            operation,
+            counts,
+            current: ItemCandidate::Index(0, initial),
+            elements,
+            ceiling: config.maximum().map(ItemCandidate::Element),
+            meter: Meter::new(&config),
+            config,
+            expressions: BinaryHeap::new(),
+        };
+        for end in 0..engine.elements.len() {
+            engine.push_expressions(end);
+        }
+        Ok(engine)
+    }
+
+    /// Pushes the expression streams of the element at `end`, one for every
+    /// number of summands.
+    fn push_expressions(&mut self, end: usize) {
+        for t in &self.counts {
+            for summands in S::accompany(&self.parameters, end, t - 1) {
+                if let Option::Some(data) = Data::new(
+                    &self.parameters,
+                    &self.operation,
+                    end,
+                    summands,
+                    &self.elements,
+                ) {
+                    self.expressions.push(data);
+                    self.meter.created();
+                }
+            }
+        }
+    }
+
+    fn unexpressable(&mut self, c: usize) -> Option<usize> {
+        self.elements.push(c);
+        self.push_expressions(self.elements.len() - 1);
+        self.current = self.current.next();
+        Option::Some(c)
+    }
+
+    fn expressable(&mut self) {
+        self.progress_minimum_expression();
+        self.current = self.current.next();
+    }
+
+    fn progress_minimum_expression(&mut self) {
+        self.meter.progressed();
+        let mut data = self.expressions.peek_mut().unwrap(/* safe because we peeked */);
+        if !data.progress(&self.parameters, &self.operation, &self.elements) {
+            PeekMut::pop(data);
+        }
+    }
+}
+
+impl<S: Summands, O: Operation> Budgeted for Engine<S, O> {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.meter.exhausted()
+    }
+}
+
+impl<S: Summands, O: Operation> Instrumented for Engine<S, O> {
+    fn counters(&self) -> Counters {
+        self.meter.counters(self.expressions.len())
+    }
+}
+
+/// Determines if `element` is the combination of `t` summands from `prefix`.
+pub(crate) fn is_sum<S: Summands, O: Operation>(
+    parameters: &S::Parameters,
+    operation: &O,
+    prefix: &[usize],
+    t: usize,
+    element: usize,
+) -> bool {
+    let mut summands = S::choose(parameters, prefix.len(), t);
+    while let Option::Some(indices) = summands.advance() {
+        if S::evaluate(parameters, operation, indices, prefix, Option::None) == element {
+            return true;
+        }
+    }
+    false
+}
+
+impl<S: Summands, O: Operation> Iterator for Engine<S, O> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut result = Option::None;
+        while result.is_none()
+            && self
+                .ceiling
+                .as_ref()
+                .map(|max| self.current < *max)
+                .unwrap_or(true)
+        {
+            match &self.current {
+                ItemCandidate::Index(index, initial) => {
+                    result = Option::Some(initial[*index]);
+                    self.current = self.current.next();
+                }
+                ItemCandidate::Element(c) if !self.meter.examine(*c, self.expressions.len()) => {
+                    break;
+                }
+                ItemCandidate::Element(c) if self.config.is_excluded(*c) => {
+                    self.current = self.current.next();
+                }
+                ItemCandidate::Element(c) if self.config.is_forced(*c) => {
+                    result = self.unexpressable(*c);
+                }
+                ItemCandidate::Element(c) => match self.expressions.peek() {
+                    Option::Some(peek) => {
+                        if *c < peek.n {
+                            result = self.unexpressable(*c);
+                        } else if *c == peek.n {
+                            self.expressable();
+                        } else {
+                            self.progress_minimum_expression();
+                        }
+                    }
+                    Option::None => {
+                        result = self.unexpressable(*c);
+                    }
+                },
+            }
+        }
+        result
+    }
+}
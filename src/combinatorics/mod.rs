@@ -1,7 +1,71 @@
 //! The `combinatorics` module offers various combinatoric data structures.
 
 pub mod combination;
+pub mod form;
+mod heap;
+pub mod operation;
 pub mod word;
+pub mod xor;
 
 pub use combination::Combinations;
+pub use operation::{Lcm, Operation, Product, Sum, SumOfSquares};
 pub use word::Words;
+
+pub(crate) use combination::{rank_indices, unrank_indices};
+
+/// The binomial coefficient `n` choose `k`, or `None` when it does not fit in
+/// a `usize`.
+///
+/// ```
+/// # use sequence::combinatorics::binomial;
+/// assert_eq!(binomial(5, 2), Some(10));
+/// assert_eq!(binomial(2, 5), Some(0));
+/// assert_eq!(binomial(100, 50), None);
+/// ```
+pub fn binomial(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Option::Some(0);
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result.checked_mul((n - i) as u128)? / (i + 1) as u128;
+        if result > usize::MAX as u128 {
+            return Option::None;
+        }
+    }
+    Option::Some(result as usize)
+}
+
+/// Draws a uniformly distributed number below `bound` from a source of
+/// uniformly distributed 64-bit numbers, rejecting the biased draws.
+fn uniform_below<R>(bound: usize, random: &mut R) -> usize
+where
+    R: FnMut() -> u64,
+{
+    let bound = bound as u64;
+    let threshold = bound.wrapping_neg() % bound;
+    loop {
+        let draw = random();
+        if draw >= threshold {
+            return (draw % bound) as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::binomial;
+
+    #[test]
+    fn binomial_agrees_with_pascals_triangle() {
+        for n in 1..30 {
+            for k in 1..n {
+                assert_eq!(
+                    binomial(n, k),
+                    Option::Some(binomial(n - 1, k - 1).unwrap() + binomial(n - 1, k).unwrap())
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! The `operation` module describes how the summands of an expression are
+//! combined.
+//!
+//! The heap engine searches the expressions of every stream in the order of
+//! their values, guided by a lower bound of the values that never decreases
+//! when an operand increases. A monotone operation, whose result never
+//! decreases when an operand increases, is its own bound. The least common
+//! multiple is not monotone, since `lcm(2, 4) < lcm(2, 3)`, but it is bounded
+//! by its largest operand.
+
+/// An operation that combines the operands of an expression.
+pub trait Operation {
+    /// Combines the operands into the value of the expression.
+    fn combine<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>;
+
+    /// A lower bound of the combination that never decreases when an operand
+    /// increases. A monotone operation is its own bound.
+    fn bound<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        self.combine(operands)
+    }
+}
+
+/// The sum of the operands, which gives the subsumfree sequences.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sum;
+
+impl Operation for Sum {
+    fn combine<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        operands.sum()
+    }
+}
+
+/// The product of the operands, which gives the product-free sequences.
+///
+/// Products that do not fit in a `usize` saturate.
+///
+/// ```
+/// # use sequence::combinatorics::operation::{Operation, Product};
+/// assert_eq!(Product.combine([2, 3, 7].into_iter()), 42);
+/// assert_eq!(Product.combine([usize::MAX, 2].into_iter()), usize::MAX);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Product;
+
+impl Operation for Product {
+    fn combine<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        operands.fold(1, |product, operand| product.saturating_mul(operand))
+    }
+}
+
+/// The sum of the squares of the operands.
+///
+/// Sums that do not fit in a `usize` saturate.
+///
+/// ```
+/// # use sequence::combinatorics::operation::{Operation, SumOfSquares};
+/// assert_eq!(SumOfSquares.combine([1, 2, 3].into_iter()), 14);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SumOfSquares;
+
+impl Operation for SumOfSquares {
+    fn combine<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        operands.fold(0, |sum, operand| {
+            sum.saturating_add(operand.saturating_mul(operand))
+        })
+    }
+}
+
+/// The least common multiple of the operands, which gives the lcm-free
+/// sequences.
+///
+/// Multiples that do not fit in a `usize` saturate.
+///
+/// ```
+/// # use sequence::combinatorics::operation::{Lcm, Operation};
+/// assert_eq!(Lcm.combine([4, 6, 10].into_iter()), 60);
+/// assert_eq!(Lcm.bound([4, 6, 10].into_iter()), 10);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Lcm;
+
+impl Operation for Lcm {
+    fn combine<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        operands.fold(1, |multiple, operand| {
+            (multiple / gcd(multiple, operand)).saturating_mul(operand)
+        })
+    }
+
+    fn bound<I>(&self, operands: I) -> usize
+    where
+        I: Iterator<Item = usize>,
+    {
+        operands.max().unwrap_or(1)
+    }
+}
+
+/// The greatest common divisor.
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
@@ -0,0 +1,445 @@
+//! Words with an upper bound on every letter.
+
+use crate::Error;
+
+/// Enumerates the vectors `m` with `m[i] <= bounds[i]` and
+/// `sum(m[i] * weights[i]) == total` in the same order as `Words`, i.e.
+/// lexicographically descending.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Weighted {
+    weights: Vec<usize>,
+    bounds: Vec<usize>,
+    /// `reachable[j][r]` tells if the positions from `j` onwards can sum to `r`.
+    reachable: Vec<Vec<bool>>,
+    started: bool,
+    current: Option<Vec<usize>>,
+}
+
+impl Weighted {
+    pub(super) fn new(weights: Vec<usize>, bounds: Vec<usize>, total: usize) -> Self {
+        let length = weights.len();
+        let mut reachable = vec![vec![false; total + 1]; length + 1];
+        reachable[length][0] = true;
+        for j in (0..length).rev() {
+            for r in 0..=total {
+                reachable[j][r] = (0..=bounds[j])
+                    .take_while(|m| m * weights[j] <= r)
+                    .any(|m| reachable[j + 1][r - m * weights[j]]);
+            }
+        }
+        let mut current = vec![0; length];
+        let current = if complete(&weights, &bounds, &reachable, &mut current, 0, total) {
+            Option::Some(current)
+        } else {
+            Option::None
+        };
+        Self {
+            weights,
+            bounds,
+            reachable,
+            started: false,
+            current,
+        }
+    }
+
+    pub(super) fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(word) => {
+                    !successor(&self.weights, &self.bounds, &self.reachable, word)
+                }
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
+            }
+        }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+/// Fills `word` from position `j` onwards with the largest completion that
+/// sums to `remainder`, returning whether there is one.
+fn complete(
+    weights: &[usize],
+    bounds: &[usize],
+    reachable: &[Vec<bool>],
+    word: &mut [usize],
+    j: usize,
+    remainder: usize,
+) -> bool {
+    if !reachable[j][remainder] {
+        return false;
+    }
+    let mut remainder = remainder;
+    for i in j..word.len() {
+        let mut m = bounds[i].min(remainder / weights[i]);
+        while !reachable[i + 1][remainder - m * weights[i]] {
+            m -= 1;
+        }
+        word[i] = m;
+        remainder -= m * weights[i];
+    }
+    true
+}
+
+/// Replaces `word` by its successor, returning whether there is one.
+fn successor(
+    weights: &[usize],
+    bounds: &[usize],
+    reachable: &[Vec<bool>],
+    word: &mut [usize],
+) -> bool {
+    let mut remainder = 0;
+    for i in (0..word.len().saturating_sub(1)).rev() {
+        remainder += word[i + 1] * weights[i + 1];
+        for letter in (0..word[i]).rev() {
+            let candidate = remainder + (word[i] - letter) * weights[i];
+            if reachable[i + 1][candidate] {
+                word[i] = letter;
+                return complete(weights, bounds, reachable, word, i + 1, candidate);
+            }
+        }
+    }
+    false
+}
+
+/// An iterator for all words of certain weight where every letter is bounded.
+///
+/// The words are produced in the same order as [`Words`](super::Words). When
+/// every bound is at least the weight, the enumerations coincide.
+///
+/// ```
+/// # use sequence::combinatorics::word::BoundedWords;
+/// let actual: Vec<Vec<usize>> = BoundedWords::new(vec![1, 2, 1], 3).collect();
+/// let expected = vec![vec![1, 2, 0], vec![1, 1, 1], vec![0, 2, 1]];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct BoundedWords {
+    words: Weighted,
+}
+
+impl BoundedWords {
+    /// Creates an iterator for all words of certain weight where letter `i` is
+    /// at most `bounds[i]`. The length of the words is the number of bounds.
+    pub fn new(bounds: Vec<usize>, weight: usize) -> Self {
+        Self {
+            words: Weighted::new(vec![1; bounds.len()], bounds, weight),
+        }
+    }
+
+    /// Creates an iterator for all words of certain length and weight where
+    /// every letter is at most `bound`.
+    pub fn uniform(length: usize, weight: usize, bound: usize) -> Self {
+        Self::new(vec![bound; length], weight)
+    }
+
+    /// Advances to the next word without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        self.words.advance()
+    }
+}
+
+impl Iterator for BoundedWords {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|word| word.to_vec())
+    }
+}
+
+/// An iterator for bounded words represented by the non-decreasing indices of
+/// the letters, each index repeated as often as its weight.
+///
+/// The words are produced in the same order as [`BoundedWords`], but the state
+/// only takes space proportional to the weight.
+///
+/// ```
+/// # use sequence::combinatorics::word::BoundedIndices;
+/// let actual: Vec<Vec<usize>> = BoundedIndices::new(3, 3, 2).collect();
+/// let expected = vec![
+///     vec![0, 0, 1], vec![0, 0, 2], vec![0, 1, 1], vec![0, 1, 2],
+///     vec![0, 2, 2], vec![1, 1, 2], vec![1, 2, 2],
+/// ];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct BoundedIndices {
+    length: usize,
+    bound: usize,
+    last: usize,
+    started: bool,
+    current: Option<Vec<usize>>,
+}
+
+impl BoundedIndices {
+    /// Creates an iterator over the indices of words of certain length and
+    /// weight where every letter is at most `bound`.
+    pub fn new(length: usize, weight: usize, bound: usize) -> Self {
+        Self::with_last(length, weight, bound, bound)
+    }
+
+    /// Creates an iterator over the indices of words of certain length and
+    /// weight where every letter is at most `bound`, except the last letter
+    /// which is at most `last`.
+    pub fn with_last(length: usize, weight: usize, bound: usize, last: usize) -> Self {
+        let mut indices = Self {
+            length,
+            bound,
+            last,
+            started: false,
+            current: Option::None,
+        };
+        let mut current = vec![0; weight];
+        if indices.fill(&mut current, 0, 0) {
+            indices.current = Option::Some(current);
+        }
+        indices
+    }
+
+    fn bound_of(&self, index: usize) -> usize {
+        if index + 1 == self.length {
+            self.last
+        } else {
+            self.bound
+        }
+    }
+
+    /// Fills the indices from position `i` onwards with the smallest
+    /// completion that starts at `index`, returning whether there is one.
+    fn fill(&self, current: &mut [usize], i: usize, index: usize) -> bool {
+        let mut index = index;
+        let mut used = 0;
+        for position in current.iter_mut().skip(i) {
+            while index < self.length && used == self.bound_of(index) {
+                index += 1;
+                used = 0;
+            }
+            if index == self.length {
+                return false;
+            }
+            *position = index;
+            used += 1;
+        }
+        true
+    }
+
+    /// Whether `indices` are the non-decreasing indices of a word of this
+    /// enumeration.
+    pub fn contains(&self, indices: &[usize]) -> bool {
+        indices.windows(2).all(|pair| pair[0] <= pair[1])
+            && indices
+                .chunk_by(|l, r| l == r)
+                .all(|run| run[0] < self.length && run.len() <= self.bound_of(run[0]))
+    }
+
+    /// Advances to the next word without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let mut current = self.current.take();
+            if let Option::Some(indices) = &mut current {
+                let successor = (0..indices.len()).rev().any(|i| {
+                    let index = indices[i] + 1;
+                    index < self.length && self.fill(indices, i, index)
+                });
+                if !successor {
+                    current = Option::None;
+                }
+            }
+            self.current = current;
+        }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+impl Iterator for BoundedIndices {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|indices| indices.to_vec())
+    }
+}
+
+/// An iterator for the partitions of a number into parts from a given set.
+///
+/// A partition is represented by the multiplicity of every part, in the same
+/// vector representation and order as [`Words`](super::Words).
+///
+/// ```
+/// # use sequence::combinatorics::word::Partitions;
+/// let actual: Vec<Vec<usize>> = Partitions::new(vec![1, 2, 5], 5).collect();
+/// let expected = vec![
+///     vec![5, 0, 0],
+///     vec![3, 1, 0],
+///     vec![1, 2, 0],
+///     vec![0, 0, 1],
+/// ];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Partitions {
+    parts: Vec<usize>,
+    partitions: Weighted,
+}
+
+impl Partitions {
+    /// Creates an iterator for the partitions of `n` into `parts`, where
+    /// every part can be used arbitrarily often.
+    ///
+    /// # Panics
+    /// Panics when a part is zero, see [`Partitions::try_new`].
+    pub fn new(parts: Vec<usize>, n: usize) -> Self {
+        Self::try_new(parts, n).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates an iterator for the partitions of `n` into `parts`, where
+    /// every part can be used arbitrarily often, failing when a part is zero.
+    pub fn try_new(parts: Vec<usize>, n: usize) -> Result<Self, Error> {
+        let bounds = parts.iter().map(|part| n / part.max(&1)).collect();
+        Self::bounded(parts, bounds, n)
+    }
+
+    /// Creates an iterator for the partitions of `n` into distinct `parts`.
+    ///
+    /// # Panics
+    /// Panics when a part is zero, see [`Partitions::try_distinct`].
+    pub fn distinct(parts: Vec<usize>, n: usize) -> Self {
+        Self::try_distinct(parts, n).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates an iterator for the partitions of `n` into distinct `parts`,
+    /// failing when a part is zero.
+    ///
+    /// ```
+    /// # use sequence::Error;
+    /// # use sequence::combinatorics::word::Partitions;
+    /// assert_eq!(Partitions::try_distinct(vec![1, 2, 3], 3).unwrap().count(), 2);
+    /// assert_eq!(Partitions::try_distinct(vec![0, 1], 1), Err(Error::ZeroPart));
+    /// ```
+    pub fn try_distinct(parts: Vec<usize>, n: usize) -> Result<Self, Error> {
+        let bounds = vec![1; parts.len()];
+        Self::bounded(parts, bounds, n)
+    }
+
+    /// Creates an iterator for the partitions of `n` into distinct parts.
+    ///
+    /// The multiplicity of part `p` is at index `p - 1`.
+    ///
+    /// ```
+    /// # use sequence::combinatorics::word::Partitions;
+    /// assert_eq!(Partitions::into_distinct_parts(6).count(), 4);
+    /// ```
+    pub fn into_distinct_parts(n: usize) -> Self {
+        Self::distinct((1..=n).collect(), n)
+    }
+
+    fn bounded(parts: Vec<usize>, bounds: Vec<usize>, n: usize) -> Result<Self, Error> {
+        if parts.contains(&0) {
+            return Err(Error::ZeroPart);
+        }
+        Ok(Self {
+            partitions: Weighted::new(parts.clone(), bounds, n),
+            parts,
+        })
+    }
+
+    /// The parts that the multiplicities refer to.
+    pub fn parts(&self) -> &[usize] {
+        &self.parts
+    }
+
+    /// Advances to the next partition without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        self.partitions.advance()
+    }
+}
+
+impl Iterator for Partitions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|partition| partition.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedIndices, BoundedWords, Partitions};
+    use crate::combinatorics::Words;
+
+    #[test]
+    fn bounded_words_with_large_bounds_are_words() {
+        let actual: Vec<Vec<usize>> = BoundedWords::uniform(4, 3, 3).collect();
+        let expected: Vec<Vec<usize>> = Words::new(4, 3).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn bounded_words_are_the_words_within_bounds() {
+        let bounds = vec![2, 0, 3, 1];
+        let actual: Vec<Vec<usize>> = BoundedWords::new(bounds.clone(), 4).collect();
+        let expected: Vec<Vec<usize>> = Words::new(4, 4)
+            .filter(|word| {
+                word.iter()
+                    .zip(&bounds)
+                    .all(|(letter, bound)| letter <= bound)
+            })
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn bounded_indices_follow_the_order_of_bounded_words() {
+        let actual: Vec<Vec<usize>> = BoundedIndices::with_last(5, 4, 2, 1)
+            .map(|indices| {
+                let mut word = vec![0; 5];
+                for index in indices {
+                    word[index] += 1;
+                }
+                word
+            })
+            .collect();
+        let expected: Vec<Vec<usize>> = BoundedWords::new(vec![2, 2, 2, 2, 1], 4).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn bounded_words_without_room_are_empty() {
+        assert_eq!(BoundedWords::uniform(3, 4, 1).count(), 0);
+    }
+
+    #[test]
+    fn partitions_into_distinct_parts_are_counted_correctly() {
+        let actual: Vec<usize> = (1..=12)
+            .map(|n| Partitions::into_distinct_parts(n).count())
+            .collect();
+        let expected: Vec<usize> = vec![1, 1, 2, 2, 3, 4, 5, 6, 8, 10, 12, 15];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn partitions_sum_to_their_number() {
+        let partitions = Partitions::new(vec![3, 4, 7], 20);
+        let parts = partitions.parts().to_vec();
+        let all: Vec<Vec<usize>> = partitions.collect();
+
+        assert_eq!(all.len(), 4);
+        for partition in all {
+            assert_eq!(
+                partition
+                    .iter()
+                    .zip(&parts)
+                    .map(|(m, p)| m * p)
+                    .sum::<usize>(),
+                20
+            );
+        }
+    }
+}
@@ -1,12 +1,45 @@
 //! A word is a sequence of weights of a certain length and weight.
+//!
+//! Besides all words, the module offers words with bounded letters and
+//! partitions of a number into parts from a given set, both in the same
+//! vector representation.
+mod bounded;
 mod sequence;
 
-pub use sequence::Sequence;
+pub use bounded::{BoundedIndices, BoundedWords, Partitions};
+pub use sequence::{BoundedSequence, Sequence};
+
+use crate::Error;
+use crate::combinatorics::{binomial, rank_indices, uniform_below, unrank_indices};
 
 /// An iterator for all words of certain length and weight.
+///
+/// Besides the `Iterator` implementation, which allocates a fresh vector for
+/// every word, [`Words::advance`] steps through the words in place.
+///
+/// The words can be ranked, unranked and sampled, which allows one to split
+/// the enumeration in chunks.
+///
+/// The number of words quickly outgrows a `usize`, so [`Words::len`] returns
+/// `None` when it does not fit, and `Words` does not implement
+/// `ExactSizeIterator`, whose length could not be trusted.
+///
+/// ```
+/// # use sequence::combinatorics::Words;
+/// let words = Words::new(3, 2);
+/// assert_eq!(words.len(), Some(6));
+/// assert_eq!(words.unrank(3), Some(vec![0, 2, 0]));
+/// assert_eq!(words.rank(&[0, 2, 0]), Some(3));
+///
+/// let chunk: Vec<Vec<usize>> = Words::new(3, 2).skip(4).collect();
+/// assert_eq!(chunk, vec![vec![0, 1, 1], vec![0, 0, 2]]);
+/// ```
 #[derive(Debug, PartialEq, Eq)]
 pub struct Words {
+    length: usize,
     weight: usize,
+    started: bool,
+    position: usize,
     current: Option<Vec<usize>>,
 }
 
@@ -18,54 +51,290 @@ impl Words {
             current[0] = weight;
         }
         Self {
+            length,
             weight,
+            started: false,
+            position: 0,
             current: Option::Some(current),
         }
     }
+
+    /// Creates an iterator for all words of certain length and weight,
+    /// failing when no such word exists.
+    ///
+    /// Unlike [`Words::new`], which produces the empty word for length 0, a
+    /// word without letters is only accepted with weight 0.
+    ///
+    /// ```
+    /// # use sequence::Error;
+    /// # use sequence::combinatorics::Words;
+    /// assert!(Words::try_new(3, 5).is_ok());
+    /// assert_eq!(Words::try_new(0, 5), Err(Error::EmptyWord { weight: 5 }));
+    /// ```
+    pub fn try_new(length: usize, weight: usize) -> Result<Self, Error> {
+        if length == 0 && weight > 0 {
+            return Err(Error::EmptyWord { weight });
+        }
+        Ok(Self::new(length, weight))
+    }
+
+    /// The total number of words, or `None` when it does not fit in a
+    /// `usize`.
+    fn total(&self) -> Option<usize> {
+        if self.length == 0 {
+            Option::Some(1)
+        } else {
+            binomial(self.length + self.weight - 1, self.weight)
+        }
+    }
+
+    /// Determines the position of a word in the enumeration.
+    ///
+    /// Returns `None` when the word does not have the length and weight of
+    /// this enumeration.
+    pub fn rank(&self, word: &[usize]) -> Option<usize> {
+        if word.len() != self.length {
+            return Option::None;
+        }
+        if self.length == 0 {
+            return Option::Some(0);
+        }
+        if word.iter().sum::<usize>() != self.weight {
+            return Option::None;
+        }
+        // A word corresponds to a combination by separating the letters.
+        let indices: Vec<usize> = word
+            .iter()
+            .enumerate()
+            .flat_map(|(index, letter)| std::iter::repeat_n(index, *letter))
+            .enumerate()
+            .map(|(offset, index)| index + offset)
+            .collect();
+        rank_indices(self.length + self.weight - 1, &indices)
+    }
+
+    /// Determines the word at a position in the enumeration.
+    ///
+    /// Returns `None` when the position is beyond the last word.
+    pub fn unrank(&self, rank: usize) -> Option<Vec<usize>> {
+        if self.length == 0 {
+            return if rank == 0 {
+                Option::Some(vec![])
+            } else {
+                Option::None
+            };
+        }
+        unrank_indices(self.length + self.weight - 1, self.weight, rank).map(|indices| {
+            let mut word = vec![0; self.length];
+            for (offset, index) in indices.into_iter().enumerate() {
+                word[index - offset] += 1;
+            }
+            word
+        })
+    }
+
+    /// The number of words that are still to come, or `None` when it
+    /// does not fit in a `usize`.
+    pub fn len(&self) -> Option<usize> {
+        self.total()
+            .map(|total| total.saturating_sub(self.position))
+    }
+
+    /// Whether no words are to come.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Option::Some(0)
+    }
+
+    /// Moves the iterator so that it continues with the word at position
+    /// `rank`.
+    pub fn seek(&mut self, rank: usize) {
+        self.current = self.unrank(rank);
+        self.started = false;
+        self.position = rank;
+    }
+
+    /// Draws a uniformly distributed word.
+    ///
+    /// The `random` source should produce uniformly distributed 64-bit
+    /// numbers.
+    ///
+    /// ```
+    /// # use sequence::combinatorics::Words;
+    /// let mut state: u64 = 37;
+    /// let mut xorshift = || {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     state
+    /// };
+    /// let word = Words::new(4, 6).sample(&mut xorshift).unwrap();
+    /// assert_eq!(word.len(), 4);
+    /// assert_eq!(word.iter().sum::<usize>(), 6);
+    /// ```
+    pub fn sample<R>(&self, random: &mut R) -> Option<Vec<usize>>
+    where
+        R: FnMut() -> u64,
+    {
+        // the ranks that do not fit in a `usize` are never drawn
+        self.unrank(uniform_below(self.total().unwrap_or(usize::MAX), random))
+    }
+
+    /// Advances to the next word without allocating.
+    ///
+    /// ```
+    /// # use sequence::combinatorics::Words;
+    /// let mut words = Words::new(3, 2);
+    /// let mut count = 0;
+    /// while let Some(word) = words.advance() {
+    ///     assert_eq!(word.iter().sum::<usize>(), 2);
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 6);
+    /// ```
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(word) => !successor(word, self.weight),
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
+            }
+        }
+        self.started = true;
+        if self.current.is_some() {
+            self.position += 1;
+        }
+        self.current.as_deref()
+    }
+}
+
+/// Replaces `word` by its successor, returning whether there is one.
+fn successor(word: &mut [usize], weight: usize) -> bool {
+    if word.is_empty() {
+        return false;
+    }
+    let mut index = word.len() - 1;
+    if word[index] < weight {
+        let residue = word[index];
+        word[index] = 0;
+        while index > 0 && word[index] == 0 {
+            index -= 1;
+        }
+
+        if word[index] > 1 {
+            word[index] -= 1;
+            word[index + 1] = residue + 1;
+        } else {
+            word[index] = 0;
+            word[index + 1] = residue + 1;
+        }
+        true
+    } else {
+        false
+    }
 }
 
 impl Iterator for Words {
     type Item = Vec<usize>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match &self.current {
-            Option::Some(current) => {
-                let mut current_word: Vec<usize> = current.to_vec();
-                let result = Option::Some(current_word.to_vec());
-                if current_word.len() > 0 {
-                    let n = current_word.len() - 1;
-                    let mut index = n;
-                    if current_word[index] < self.weight {
-                        let residue = current_word[index];
-                        current_word[index] = 0;
-                        while index > 0 && current_word[index] == 0 {
-                            index -= 1;
-                        }
+        self.advance().map(|word| word.to_vec())
+    }
 
-                        if current_word[index] > 1 {
-                            current_word[index] -= 1;
-                            current_word[index + 1] = residue + 1;
-                        } else {
-                            current_word[index] = 0;
-                            current_word[index + 1] = residue + 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len() {
+            Option::Some(remaining) => (remaining, Option::Some(remaining)),
+            Option::None => (usize::MAX, Option::None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 {
+            self.seek(self.position.saturating_add(n));
+        }
+        self.next()
+    }
+}
+
+/// An iterator for words represented by the non-decreasing indices of the
+/// letters, each index repeated as often as its weight.
+///
+/// The words are produced in the same order as [`Words`], but the state only
+/// takes space proportional to the weight.
+///
+/// ```
+/// # use sequence::combinatorics::word::Indices;
+/// let actual: Vec<Vec<usize>> = Indices::new(3, 2).collect();
+/// let expected = vec![
+///     vec![0, 0], vec![0, 1], vec![0, 2], vec![1, 1], vec![1, 2], vec![2, 2],
+/// ];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Indices {
+    length: usize,
+    started: bool,
+    current: Option<Vec<usize>>,
+}
+
+impl Indices {
+    /// Creates an iterator over the indices of words of certain length and
+    /// weight.
+    pub fn new(length: usize, weight: usize) -> Self {
+        Self {
+            length,
+            started: false,
+            current: if length > 0 || weight == 0 {
+                Option::Some(vec![0; weight])
+            } else {
+                Option::None
+            },
+        }
+    }
+
+    /// Advances to the next word without allocating.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.started {
+            let exhausted = match &mut self.current {
+                Option::Some(indices) => {
+                    match (0..indices.len())
+                        .rev()
+                        .find(|i| indices[*i] + 1 < self.length)
+                    {
+                        Option::Some(i) => {
+                            indices[i] += 1;
+                            let index = indices[i];
+                            for later in indices.iter_mut().skip(i + 1) {
+                                *later = index;
+                            }
+                            false
                         }
-                        self.current = Option::Some(current_word);
-                    } else {
-                        self.current = Option::None;
+                        Option::None => true,
                     }
-                } else {
-                    self.current = Option::None;
                 }
-                result
+                Option::None => true,
+            };
+            if exhausted {
+                self.current = Option::None;
             }
-            Option::None => Option::None,
         }
+        self.started = true;
+        self.current.as_deref()
+    }
+}
+
+impl Iterator for Indices {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().map(|indices| indices.to_vec())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Words;
+    use super::{Indices, Words};
 
     #[test]
     fn words_4_3_generates_all_possibilities() {
@@ -124,6 +393,71 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn indices_follow_the_order_of_words() {
+        let actual: Vec<Vec<usize>> = Indices::new(4, 3)
+            .map(|indices| {
+                let mut word = vec![0; 4];
+                for index in indices {
+                    word[index] += 1;
+                }
+                word
+            })
+            .collect();
+        let expected: Vec<Vec<usize>> = Words::new(4, 3).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn advance_visits_the_same_words_as_the_iterator() {
+        let mut words = Words::new(4, 3);
+        let mut actual: Vec<Vec<usize>> = Vec::new();
+        while let Option::Some(word) = words.advance() {
+            actual.push(word.to_vec());
+        }
+        let expected: Vec<Vec<usize>> = Words::new(4, 3).collect();
+
+        assert_eq!(actual, expected);
+        assert_eq!(words.advance(), Option::None);
+    }
+
+    #[test]
+    fn rank_and_unrank_follow_the_enumeration() {
+        let words = Words::new(4, 3);
+        for (rank, word) in Words::new(4, 3).enumerate() {
+            assert_eq!(words.rank(&word), Option::Some(rank));
+            assert_eq!(words.unrank(rank), Option::Some(word));
+        }
+        assert_eq!(words.unrank(20), Option::None);
+        assert_eq!(words.rank(&[1, 1, 0, 0]), Option::None);
+    }
+
+    #[test]
+    fn length_and_seeking_are_consistent() {
+        let mut words = Words::new(3, 5);
+        assert_eq!(words.len(), Option::Some(21));
+        words.next();
+        assert_eq!(words.nth(9), Words::new(3, 5).nth(10));
+        assert_eq!(words.size_hint(), (10, Option::Some(10)));
+        assert_eq!(words.count(), 10);
+
+        let mut single = Words::new(1, 3);
+        assert!(!single.is_empty());
+        single.next();
+        assert!(single.is_empty());
+    }
+
+    #[test]
+    fn lengths_that_overflow_are_unknown() {
+        let mut words = Words::new(100, 100);
+        assert_eq!(words.len(), Option::None);
+        assert!(!words.is_empty());
+        assert_eq!(words.size_hint(), (usize::MAX, Option::None));
+        words.next();
+        assert_eq!(words.size_hint(), (usize::MAX, Option::None));
+    }
+
     #[test]
     fn words_of_length_zero_produce_the_empty_word() {
         let actual: Vec<Vec<usize>> = Words::new(0, 37).collect();
@@ -1,119 +1,80 @@
 //! A sequence based on words.
 
-use crate::combinatorics::Words;
-use crate::tools::ItemCandidate;
-use std::collections::BinaryHeap;
-
-#[derive(Debug, PartialEq, Eq)]
-struct Data {
-    n: usize,
-    weights: Vec<usize>,
-    iterator: Words,
-}
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented};
+use crate::combinatorics::heap::{Engine, Summands};
+use crate::combinatorics::operation::Sum;
+use crate::combinatorics::word::{BoundedIndices, Indices};
+use crate::{Config, Error};
 
-impl Data {
-    fn new(t: usize, elements: &[usize]) -> Self {
-        let m = elements.len();
-        let weights = elements.into_iter().cloned().collect();
-        let mut iterator = Words::new(m, t - 1);
-
-        let n = iterator
-            .next()
-            .map(|mut word| {
-                word[m - 1] += 1;
-                word.iter().zip(&weights).map(|pair| pair.0 * pair.1).sum()
-            })
-            .unwrap_or(0);
-        Self {
-            n,
-            weights,
-            iterator,
-        }
+impl Summands for Indices {
+    type Parameters = ();
+
+    fn choose(_: &(), n: usize, t: usize) -> Self {
+        Indices::new(n, t)
     }
 
-    fn progress(self) -> Option<Self> {
-        let mut iterator = self.iterator;
-        iterator
-            .next()
-            .map(|mut word| {
-                word[self.weights.len() - 1] += 1;
-                word.iter()
-                    .zip(&self.weights)
-                    .map(|pair| pair.0 * pair.1)
-                    .sum()
-            })
-            .map(|n: usize| Data {
-                n,
-                weights: self.weights,
-                iterator,
-            })
+    fn accompany(_: &(), end: usize, t: usize) -> Vec<Self> {
+        vec![Indices::new(end + 1, t)]
     }
-}
 
-impl Ord for Data {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.n.cmp(&self.n)
+    fn advance(&mut self) -> Option<&[usize]> {
+        Indices::advance(self)
     }
-}
 
-impl PartialOrd for Data {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Option::Some(self.cmp(other))
+    fn contains(&self, indices: &[usize]) -> bool {
+        indices.windows(2).all(|pair| pair[0] <= pair[1])
+            && indices.last().is_none_or(|last| *last < self.length)
     }
 }
 
 /// The sequence
 pub struct Sequence {
-    t: usize,
-    current: ItemCandidate,
-    elements: Vec<usize>,
-    ceiling: Option<ItemCandidate>,
-    expressions: BinaryHeap<Data>,
+    engine: Engine<Indices, Sum>,
 }
 
 impl Sequence {
     /// Create a sequence with an initial segment.
+    ///
+    /// # Panics
+    /// Panics when the initial segment is invalid, see [`Sequence::try_new`].
     pub fn new(initial: Vec<usize>) -> Self {
-        Self::initialize(initial, Option::None)
+        Self::try_new(initial).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a sequence with an initial segment, failing when the initial
+    /// segment is empty, not strictly increasing or not subsumfree.
+    pub fn try_new(initial: Vec<usize>) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new())
     }
 
     /// Create a sequence with an initial segment
+    ///
+    /// # Panics
+    /// Panics when the initial segment is invalid, see [`Sequence::try_new`].
     pub fn with_maximum(initial: Vec<usize>, ceiling: usize) -> Self {
-        Self::initialize(initial, Option::Some(ItemCandidate::Element(ceiling)))
-    }
-
-    fn initialize(initial: Vec<usize>, ceiling: Option<ItemCandidate>) -> Self {
-        let elements: Vec<usize> = initial.iter().cloned().collect();
-        let n = initial.len();
-        let mut expressions: BinaryHeap<Data> = BinaryHeap::new();
-        expressions.push(Data::new(n, &initial));
-        Self {
-            t: n,
-            current: ItemCandidate::Index(0, initial),
-            elements,
-            ceiling,
-            expressions,
-        }
+        Self::try_with_maximum(initial, ceiling).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    fn unexpressable(&mut self, c: usize) -> Option<usize> {
-        self.elements.push(c);
-        let prefix: Vec<usize> = self.elements.iter().cloned().collect();
-        self.expressions.push(Data::new(self.t, &prefix));
-        self.current = self.current.next();
-        Option::Some(c)
+    /// Create a sequence with an initial segment and a ceiling, failing when
+    /// the initial segment is invalid.
+    pub fn try_with_maximum(initial: Vec<usize>, ceiling: usize) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new().ceiling(ceiling))
     }
 
-    fn expressable(&mut self) {
-        self.progress_minimum_expression();
-        self.current = self.current.next();
+    /// Create a sequence with an initial segment and a [`Config`].
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the configuration is invalid, see
+    /// [`Sequence::try_with_config`].
+    pub fn with_config(initial: Vec<usize>, config: Config) -> Self {
+        Self::try_with_config(initial, config).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    fn progress_minimum_expression(&mut self) {
-        let data = self.expressions.pop().unwrap(/* safe because we peeked */);
-        if let Option::Some(next) = data.progress() {
-            self.expressions.push(next);
-        }
+    /// Create a sequence with an initial segment and a [`Config`], failing
+    /// when the initial segment is invalid or when the configuration asks for
+    /// zero summands.
+    pub fn try_with_config(initial: Vec<usize>, config: Config) -> Result<Self, Error> {
+        Engine::initialize((), Sum, initial, config).map(|engine| Self { engine })
     }
 }
 
@@ -121,56 +82,251 @@ impl Iterator for Sequence {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut result = Option::None;
-        while result.is_none()
-            && self
-                .ceiling
-                .as_ref()
-                .map(|max| self.current < *max)
-                .unwrap_or(true)
-        {
-            match &self.current {
-                ItemCandidate::Index(index, initial) => {
-                    result = Option::Some(initial[*index]);
-                    self.current = self.current.next();
-                }
-                ItemCandidate::Element(c) => match self.expressions.peek() {
-                    Option::Some(peek) => {
-                        if *c < peek.n {
-                            result = self.unexpressable(*c);
-                        } else if *c == peek.n {
-                            self.expressable();
-                        } else {
-                            self.progress_minimum_expression();
-                        }
-                    }
-                    Option::None => {
-                        result = self.unexpressable(*c);
-                    }
-                },
-            }
+        self.engine.next()
+    }
+}
+
+impl Budgeted for Sequence {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.engine.exhausted()
+    }
+}
+
+impl Instrumented for Sequence {
+    fn counters(&self) -> Counters {
+        self.engine.counters()
+    }
+}
+
+impl Summands for BoundedIndices {
+    type Parameters = usize;
+
+    fn choose(multiplicity: &usize, n: usize, t: usize) -> Self {
+        BoundedIndices::new(n, t, *multiplicity)
+    }
+
+    fn accompany(multiplicity: &usize, end: usize, t: usize) -> Vec<Self> {
+        vec![BoundedIndices::with_last(
+            end + 1,
+            t,
+            *multiplicity,
+            *multiplicity - 1,
+        )]
+    }
+
+    fn advance(&mut self) -> Option<&[usize]> {
+        BoundedIndices::advance(self)
+    }
+
+    fn contains(&self, indices: &[usize]) -> bool {
+        BoundedIndices::contains(self, indices)
+    }
+}
+
+/// The sequence where every element occurs at most a certain number of times
+/// in an expression.
+///
+/// A multiplicity of 1 yields the same sequence as
+/// [`combination::Sequence`](crate::combinatorics::combination::Sequence),
+/// while a multiplicity of at least the number of summands yields the same
+/// sequence as [`Sequence`].
+///
+/// ```
+/// # use sequence::combinatorics::word::BoundedSequence;
+/// let actual: Vec<usize> = BoundedSequence::new(vec![2, 3, 10], 2).take(6).collect();
+/// let expected: Vec<usize> = vec![2, 3, 10, 11, 12, 13];
+/// assert_eq!(actual, expected);
+/// ```
+pub struct BoundedSequence {
+    engine: Engine<BoundedIndices, Sum>,
+}
+
+impl BoundedSequence {
+    /// Create a sequence with an initial segment where every element occurs
+    /// at most `multiplicity` times in an expression.
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the multiplicity is invalid, see
+    /// [`BoundedSequence::try_new`].
+    pub fn new(initial: Vec<usize>, multiplicity: usize) -> Self {
+        Self::try_new(initial, multiplicity).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a sequence with an initial segment where every element occurs
+    /// at most `multiplicity` times in an expression, failing when the
+    /// multiplicity is zero or the initial segment is empty, not strictly
+    /// increasing or not subsumfree.
+    pub fn try_new(initial: Vec<usize>, multiplicity: usize) -> Result<Self, Error> {
+        Self::try_with_config(initial, multiplicity, Config::new())
+    }
+
+    /// Create a sequence with an initial segment and a ceiling where every
+    /// element occurs at most `multiplicity` times in an expression.
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the multiplicity is invalid, see
+    /// [`BoundedSequence::try_new`].
+    pub fn with_maximum(initial: Vec<usize>, multiplicity: usize, ceiling: usize) -> Self {
+        Self::try_with_maximum(initial, multiplicity, ceiling)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a sequence with an initial segment and a ceiling where every
+    /// element occurs at most `multiplicity` times in an expression, failing
+    /// when the multiplicity or the initial segment is invalid.
+    pub fn try_with_maximum(
+        initial: Vec<usize>,
+        multiplicity: usize,
+        ceiling: usize,
+    ) -> Result<Self, Error> {
+        Self::try_with_config(initial, multiplicity, Config::new().ceiling(ceiling))
+    }
+
+    /// Create a sequence with an initial segment and a [`Config`] where every
+    /// element occurs at most `multiplicity` times in an expression.
+    ///
+    /// # Panics
+    /// Panics when the initial segment, the multiplicity or the configuration
+    /// is invalid, see [`BoundedSequence::try_with_config`].
+    pub fn with_config(initial: Vec<usize>, multiplicity: usize, config: Config) -> Self {
+        Self::try_with_config(initial, multiplicity, config)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a sequence with an initial segment and a [`Config`] where every
+    /// element occurs at most `multiplicity` times in an expression, failing
+    /// when the multiplicity, the initial segment or the configuration is
+    /// invalid.
+    pub fn try_with_config(
+        initial: Vec<usize>,
+        multiplicity: usize,
+        config: Config,
+    ) -> Result<Self, Error> {
+        if multiplicity == 0 {
+            return Err(Error::ZeroMultiplicity);
         }
-        result
+        Engine::initialize(multiplicity, Sum, initial, config).map(|engine| Self { engine })
+    }
+}
+
+impl Iterator for BoundedSequence {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.engine.next()
+    }
+}
+
+impl Budgeted for BoundedSequence {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.engine.exhausted()
+    }
+}
+
+impl Instrumented for BoundedSequence {
+    fn counters(&self) -> Counters {
+        self.engine.counters()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combinatorics::combination::Sequence as CombinationSequence;
 
     #[test]
     fn sequence_computes_correct_elements() {
-        let actual: Vec<usize> = Sequence::new(vec![1, 3, 5]).take(10).collect();
-        let expected: Vec<usize> = vec![1, 3, 5, 6, 19, 20, 28, 29, 37, 38];
+        let actual: Vec<usize> = Sequence::new(vec![2, 3, 10]).take(6).collect();
+        let expected: Vec<usize> = vec![2, 3, 10, 11, 12, 13];
 
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn sequence_with_maximum_computes_correct_elements() {
-        let actual: Vec<usize> = Sequence::with_maximum(vec![1, 3, 5], 20).take(10).collect();
-        let expected: Vec<usize> = vec![1, 3, 5, 6, 19];
+        let actual: Vec<usize> = Sequence::with_maximum(vec![2, 3, 10], 14)
+            .take(10)
+            .collect();
+        let expected: Vec<usize> = vec![2, 3, 10, 11, 12, 13];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_considers_expressions_within_the_initial_segment() {
+        let actual: Vec<usize> = Sequence::new(vec![3, 4]).take(6).collect();
+        let expected: Vec<usize> = vec![3, 4, 5, 11, 12, 13];
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn sequence_rejects_seeds_that_are_not_subsumfree() {
+        assert_eq!(
+            Sequence::try_new(vec![1, 3, 5]).err(),
+            Some(Error::Expressable {
+                index: 1,
+                element: 3
+            })
+        );
+    }
+
+    #[test]
+    fn bounded_sequence_with_multiplicity_one_is_the_combination_sequence() {
+        for initial in [vec![1, 3, 5], vec![1, 2, 3], vec![2, 5, 7, 9], vec![1, 4]] {
+            let actual: Vec<usize> =
+                BoundedSequence::with_maximum(initial.clone(), 1, 500).collect();
+            let expected: Vec<usize> = CombinationSequence::with_maximum(initial, 500).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn bounded_sequence_with_multiplicity_k_is_the_word_sequence() {
+        for initial in [vec![2, 3, 10], vec![3, 4], vec![2, 5, 7, 9]] {
+            let k = initial.len();
+            let actual: Vec<usize> =
+                BoundedSequence::with_maximum(initial.clone(), k, 500).collect();
+            let expected: Vec<usize> = Sequence::with_maximum(initial, 500).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    /// Whether `c` is the sum of `k` of the `elements`, each used at most `r`
+    /// times.
+    fn is_bounded_sum(elements: &[usize], k: usize, r: usize, c: usize) -> bool {
+        match elements.split_first() {
+            _ if k == 0 => c == 0,
+            Option::None => false,
+            Option::Some((first, rest)) => (0..=r.min(k))
+                .take_while(|times| times * first <= c)
+                .any(|times| is_bounded_sum(rest, k - times, r, c - times * first)),
+        }
+    }
+
+    #[test]
+    fn bounded_sequence_agrees_with_brute_force() {
+        for initial in [vec![1, 2, 3], vec![2, 3, 10], vec![1, 2, 4, 13]] {
+            let k = initial.len();
+            let mut expected = initial.clone();
+            for c in (initial[k - 1] + 1)..200 {
+                if !is_bounded_sum(&expected, k, 2, c) {
+                    expected.push(c);
+                }
+            }
+            let actual: Vec<usize> = BoundedSequence::with_maximum(initial, 2, 200).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn bounded_sequence_rejects_multiplicity_zero() {
+        assert_eq!(
+            BoundedSequence::try_new(vec![1, 3, 5], 0).err(),
+            Some(Error::ZeroMultiplicity)
+        );
+    }
 }
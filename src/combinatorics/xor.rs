@@ -0,0 +1,231 @@
+//! This module provides the carry-free analogue of the subsumfree sequences,
+//! where addition is replaced by XOR, i.e. addition of polynomials over
+//! GF(2).
+//!
+//! ## Example
+//! The following code demonstrates that the sequence without XORs of two
+//! distinct elements starting with 1 are the odious numbers.
+//!
+//! ```
+//! # use sequence::Config;
+//! # use sequence::combinatorics::xor::Sequence;
+//! let config = Config::new().ceiling(20).summands([2]);
+//! let actual: Vec<usize> = Sequence::with_config(vec![1], config).collect();
+//! let expected: Vec<usize> = vec![1, 2, 4, 7, 8, 11, 13, 14, 16, 19];
+//! assert_eq!(actual, expected);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented, Meter};
+use crate::combinatorics::combination::Indices;
+use crate::tools::{ItemCandidate, validate};
+use crate::{Config, Error};
+
+/// Determines if `element` is the XOR of `t` distinct elements of `prefix`.
+fn is_xor(prefix: &[usize], t: usize, element: usize) -> bool {
+    let mut summands = Indices::new(prefix.len(), t);
+    while let Option::Some(indices) = summands.advance() {
+        if indices.iter().fold(0, |xor, index| xor ^ prefix[*index]) == element {
+            return true;
+        }
+    }
+    false
+}
+
+/// XOR-free sequences with an arbitrary initial sequence.
+///
+/// XOR does not respect the order of the numbers, so instead of merging
+/// streams of expressions the sequence keeps, for every number of summands,
+/// the set of all XORs of that many distinct elements. Because the XOR of
+/// numbers below a power of two stays below it, these sets remain small.
+#[derive(Debug)]
+pub struct Sequence {
+    counts: Vec<usize>,
+    current: ItemCandidate,
+    ceiling: Option<ItemCandidate>,
+    config: Config,
+    reachable: Vec<HashSet<usize>>,
+    meter: Meter,
+}
+
+impl Sequence {
+    /// Creates a `Sequence` with an arbitrary initial sequence.
+    ///
+    /// # Panics
+    /// Panics when the initial sequence is invalid, see [`Sequence::try_new`].
+    pub fn new(initial: Vec<usize>) -> Self {
+        Self::try_new(initial).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence, failing when
+    /// the initial sequence is empty, not strictly increasing or contains the
+    /// XOR of earlier elements.
+    pub fn try_new(initial: Vec<usize>) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new())
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence and a ceiling.
+    ///
+    /// # Panics
+    /// Panics when the initial sequence is invalid, see [`Sequence::try_new`].
+    pub fn with_maximum(initial: Vec<usize>, ceiling: usize) -> Self {
+        Self::try_with_maximum(initial, ceiling).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence and a ceiling,
+    /// failing when the initial sequence is invalid.
+    pub fn try_with_maximum(initial: Vec<usize>, ceiling: usize) -> Result<Self, Error> {
+        Self::try_with_config(initial, Config::new().ceiling(ceiling))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence and a
+    /// [`Config`].
+    ///
+    /// # Panics
+    /// Panics when the initial sequence or the configuration is invalid, see
+    /// [`Sequence::try_with_config`].
+    pub fn with_config(initial: Vec<usize>, config: Config) -> Self {
+        Self::try_with_config(initial, config).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an arbitrary initial sequence and a
+    /// [`Config`], failing when the initial sequence is invalid or when the
+    /// configuration asks for zero summands.
+    pub fn try_with_config(initial: Vec<usize>, config: Config) -> Result<Self, Error> {
+        if initial.is_empty() {
+            return Err(Error::EmptyInitialSegment);
+        }
+        config.verify(&initial)?;
+        let counts = config.counts(&initial);
+        if !config.is_trusted() {
+            validate(&initial, |prefix, element| {
+                counts.iter().any(|t| is_xor(prefix, *t, element))
+            })?;
+        }
+        let maximum = counts.iter().cloned().max().unwrap_or(0);
+        let mut reachable = vec![HashSet::new(); maximum + 1];
+        reachable[0].insert(0);
+        let mut sequence = Self {
+            counts,
+            current: ItemCandidate::Index(0, initial.to_vec()),
+            ceiling: config.maximum().map(ItemCandidate::Element),
+            meter: Meter::new(&config),
+            config,
+            reachable,
+        };
+        for element in initial {
+            sequence.insert(element);
+        }
+        Ok(sequence)
+    }
+
+    fn insert(&mut self, element: usize) {
+        for t in (1..self.reachable.len()).rev() {
+            let (fewer, more) = self.reachable.split_at_mut(t);
+            more[0].extend(fewer[t - 1].iter().map(|xor| xor ^ element));
+        }
+    }
+
+    /// The number of XORs that are kept.
+    fn size(&self) -> usize {
+        self.reachable.iter().map(HashSet::len).sum()
+    }
+
+    fn expressable(&self, c: usize) -> bool {
+        self.counts.iter().any(|t| self.reachable[*t].contains(&c))
+    }
+}
+
+impl Iterator for Sequence {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut result = Option::None;
+        while result.is_none()
+            && self
+                .ceiling
+                .as_ref()
+                .map(|max| self.current < *max)
+                .unwrap_or(true)
+        {
+            match &self.current {
+                ItemCandidate::Index(index, initial) => {
+                    result = Option::Some(initial[*index]);
+                }
+                ItemCandidate::Element(c) if !self.meter.examine(*c, self.size()) => break,
+                ItemCandidate::Element(c) if self.config.is_excluded(*c) => {}
+                ItemCandidate::Element(c) => {
+                    if self.config.is_forced(*c) || !self.expressable(*c) {
+                        result = Option::Some(*c);
+                        self.insert(*c);
+                    }
+                }
+            }
+            self.current = self.current.next();
+        }
+        result
+    }
+}
+
+impl Budgeted for Sequence {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.meter.exhausted()
+    }
+}
+
+impl Instrumented for Sequence {
+    fn counters(&self) -> Counters {
+        self.meter.counters(self.size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_computes_correct_elements() {
+        let actual: Vec<usize> = Sequence::with_maximum(vec![1, 2, 3], 64).collect();
+        let expected: Vec<usize> = vec![1, 2, 3, 4, 8, 12, 16, 32, 48];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_without_xors_of_two_elements_are_the_odious_numbers() {
+        let actual: Vec<usize> = Sequence::with_maximum(vec![1, 2], 1000).collect();
+        let expected: Vec<usize> = (1..1000usize).filter(|n| n.count_ones() % 2 == 1).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_avoids_xors_with_any_number_of_summands() {
+        let config = Config::new().ceiling(64).summands([2, 3]);
+        let actual: Vec<usize> = Sequence::with_config(vec![1, 2], config).collect();
+        let expected: Vec<usize> = vec![1, 2, 4, 8, 15, 16, 32, 51];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_rejects_seeds_that_contain_xors() {
+        assert_eq!(
+            Sequence::try_with_config(vec![1, 2, 3], Config::new().summands([2])).err(),
+            Some(Error::Expressable {
+                index: 2,
+                element: 3
+            })
+        );
+    }
+
+    #[test]
+    fn sequence_respects_excluded_and_forced_elements() {
+        let config = Config::new().ceiling(40).exclude([4]).force([3]);
+        let actual: Vec<usize> = Sequence::with_config(vec![1, 2], config).collect();
+        let expected: Vec<usize> = vec![1, 2, 3, 5, 8, 12, 16, 20, 25, 29, 32, 36];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,206 @@
+//! The `config` module describes how a subsumfree sequence is generated beyond
+//! its initial segment.
+
+use std::collections::BTreeSet;
+
+use crate::Error;
+use crate::budget::{Budget, Progress};
+
+/// The configuration of a subsumfree sequence.
+///
+/// A `Config` is built by chaining its methods.
+///
+/// ```
+/// # use sequence::Config;
+/// # use sequence::combinatorics::combination::Sequence;
+/// let config = Config::new().ceiling(20).summands([2, 3]);
+/// let actual: Vec<usize> = Sequence::with_config(vec![1, 2], config).collect();
+/// let expected: Vec<usize> = vec![1, 2, 4, 8, 15];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    ceiling: Option<usize>,
+    summands: BTreeSet<usize>,
+    excluded: BTreeSet<usize>,
+    forced: BTreeSet<usize>,
+    budget: Budget,
+    progress: Option<Progress>,
+    trusted: bool,
+}
+
+impl Config {
+    /// Creates a `Config` without a ceiling, where the number of summands is
+    /// the length of the initial segment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the elements of the sequence to be below `ceiling`.
+    pub fn ceiling(mut self, ceiling: usize) -> Self {
+        self.ceiling = Option::Some(ceiling);
+        self
+    }
+
+    /// Forbids elements that are expressable with any of the given numbers of
+    /// summands.
+    ///
+    /// Without summands, the number of summands is the length of the initial
+    /// segment.
+    pub fn summands<I>(mut self, counts: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.summands = counts.into_iter().collect();
+        self
+    }
+
+    /// Keeps the given numbers out of the sequence, even when they are not
+    /// expressable.
+    ///
+    /// ```
+    /// # use sequence::Config;
+    /// # use sequence::combinatorics::combination::Sequence;
+    /// let config = Config::new().ceiling(12).exclude([4]);
+    /// let actual: Vec<usize> = Sequence::with_config(vec![1, 2, 3], config).collect();
+    /// let expected: Vec<usize> = vec![1, 2, 3, 5, 7];
+    /// assert_eq!(actual, expected);
+    /// ```
+    pub fn exclude<I>(mut self, elements: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.excluded.extend(elements);
+        self
+    }
+
+    /// Puts the given numbers in the sequence, even when they are
+    /// expressable.
+    pub fn force<I>(mut self, elements: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.forced.extend(elements);
+        self
+    }
+
+    /// Bounds the work that goes into generating the sequence, see
+    /// [`Budget`].
+    pub fn budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Reports the progress of the generation periodically, see
+    /// [`Progress`].
+    pub fn progress(mut self, progress: Progress) -> Self {
+        self.progress = Option::Some(progress);
+        self
+    }
+
+    /// Skips the validation of the initial segment, which is known to be a
+    /// prefix of a sequence generated with the same configuration.
+    pub(crate) fn trusted(mut self) -> Self {
+        self.trusted = true;
+        self
+    }
+
+    /// Whether the initial segment is trusted, see [`Config::trusted`].
+    pub(crate) fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+
+    /// The numbers kept out of the sequence.
+    pub fn excluded(&self) -> &BTreeSet<usize> {
+        &self.excluded
+    }
+
+    /// The numbers put in the sequence.
+    pub fn forced(&self) -> &BTreeSet<usize> {
+        &self.forced
+    }
+
+    /// Whether `element` is kept out of the sequence.
+    pub fn is_excluded(&self, element: usize) -> bool {
+        self.excluded.contains(&element)
+    }
+
+    /// Whether `element` is put in the sequence.
+    pub fn is_forced(&self, element: usize) -> bool {
+        self.forced.contains(&element)
+    }
+
+    /// Verifies that the configuration is consistent with the initial
+    /// segment, failing when it asks for zero summands, when a number is both
+    /// excluded and forced, when an element of the initial segment is
+    /// excluded or when a forced number does not follow the initial segment.
+    ///
+    /// ```
+    /// # use sequence::{Config, Error};
+    /// let config = Config::new().force([3]);
+    /// assert_eq!(config.verify(&[1, 2]), Ok(()));
+    /// assert_eq!(
+    ///     config.verify(&[1, 2, 4]),
+    ///     Err(Error::ForcedInitial { element: 3, last: 4 })
+    /// );
+    /// ```
+    pub fn verify(&self, initial: &[usize]) -> Result<(), Error> {
+        self.verify_options(initial)?;
+        // a trusted initial segment already holds the forced numbers
+        if self.trusted {
+            return Ok(());
+        }
+        if let Option::Some(index) = initial.iter().position(|e| self.is_excluded(*e)) {
+            return Err(Error::ExcludedInitial {
+                index,
+                element: initial[index],
+            });
+        }
+        match (self.forced.first(), initial.last()) {
+            (Option::Some(element), Option::Some(last)) if element <= last => {
+                Err(Error::ForcedInitial {
+                    element: *element,
+                    last: *last,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Verifies the part of the configuration that does not depend on the
+    /// elements of the initial segment, which may be points.
+    pub(crate) fn verify_options<T>(&self, initial: &[T]) -> Result<(), Error> {
+        if self.counts(initial).contains(&0) {
+            return Err(Error::ZeroSummands);
+        }
+        match self.excluded.intersection(&self.forced).next() {
+            Option::Some(element) => Err(Error::ExcludedAndForced { element: *element }),
+            Option::None => Ok(()),
+        }
+    }
+
+    /// The ceiling of the sequence, if any.
+    pub fn maximum(&self) -> Option<usize> {
+        self.ceiling
+    }
+
+    /// The budget of the sequence.
+    pub fn limits(&self) -> Budget {
+        self.budget
+    }
+
+    /// How the progress of the generation is reported, if at all.
+    pub fn reporter(&self) -> Option<&Progress> {
+        self.progress.as_ref()
+    }
+
+    /// The numbers of summands, defaulting to the length of the initial
+    /// segment.
+    pub fn counts<T>(&self, initial: &[T]) -> Vec<usize> {
+        if self.summands.is_empty() {
+            vec![initial.len()]
+        } else {
+            self.summands.iter().cloned().collect()
+        }
+    }
+}
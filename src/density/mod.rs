@@ -1,6 +1,6 @@
 //! The density module fascilitates experiments on determining how dense
 //! certain k-subsumfree sets are available.
-use std::iter::{empty, once};
+use std::ops::ControlFlow;
 
 use crate::combinatorics::Combinations;
 use crate::combinatorics::Words;
@@ -8,21 +8,23 @@ use crate::combinatorics::Words;
 /// Determines the maximum number of residues in a k-subsumfree depending on
 /// a certain modulus.
 pub fn maximum(k: usize, modulus: usize) -> (usize, Vec<usize>) {
-    let all_residues: Vec<usize> = (0..modulus).into_iter().collect();
+    let all_residues: Vec<usize> = (0..modulus).collect();
     let mut maximum = 0;
     let mut example: Vec<usize> = vec![];
+    let mut selected_residues: Vec<usize> = Vec::with_capacity(modulus);
     let mut n = 1;
     while n <= modulus {
         let mut found = false;
-        for selected_residues in Combinations::new(modulus, n).map(|characteristic_word| {
-            all_residues
-                .iter()
-                .cloned()
-                .zip(characteristic_word)
-                .filter(|(_, b)| *b == 1)
-                .map(|(n, _)| n)
-                .collect::<Vec<usize>>()
-        }) {
+        let mut combinations = Combinations::new(modulus, n);
+        while let Option::Some(characteristic_word) = combinations.advance() {
+            selected_residues.clear();
+            selected_residues.extend(
+                all_residues
+                    .iter()
+                    .zip(characteristic_word)
+                    .filter(|(_, b)| **b == 1)
+                    .map(|(n, _)| *n),
+            );
             if is_subsumfree(k, modulus, &selected_residues) {
                 found = true;
                 if n > maximum {
@@ -70,16 +72,21 @@ fn extend(
         .collect();
     if residues.len() + candidates.len() > *maximum {
         // we might have a chance to beat the current record
-        if candidates.len() > 0 {
+        if !candidates.is_empty() {
             'candidate_loop: for candidate in candidates {
-                let mut new_covered: Vec<bool> = covered.iter().cloned().collect();
-                let mut new_residues: Vec<usize> = residues.iter().cloned().collect();
+                let mut new_covered: Vec<bool> = covered.to_vec();
+                let mut new_residues: Vec<usize> = residues.to_vec();
                 new_residues.push(candidate);
-                for expression in expressions(k, modulus, &new_residues) {
+                let flow = expressions(k, modulus, &new_residues, |expression| {
                     if new_residues.contains(&expression) {
-                        continue 'candidate_loop;
+                        ControlFlow::Break(())
+                    } else {
+                        new_covered[expression] = true;
+                        ControlFlow::Continue(())
                     }
-                    new_covered[expression] = true;
+                });
+                if flow.is_break() {
+                    continue 'candidate_loop;
                 }
                 extend(
                     k,
@@ -99,43 +106,30 @@ fn extend(
     }
 }
 
-fn expressions<'a>(
-    k: usize,
-    modulus: usize,
-    generators: &'a [usize],
-) -> impl Iterator<Item = usize> + 'a {
-    let mut iterator: Box<dyn Iterator<Item = usize>> = Box::new(empty::<usize>());
+/// Visits all k-expressions of the generators that involve the last generator.
+fn expressions<F>(k: usize, modulus: usize, generators: &[usize], mut visit: F) -> ControlFlow<()>
+where
+    F: FnMut(usize) -> ControlFlow<()>,
+{
     if generators.len() > 1 {
+        let (last, others) = generators.split_last().unwrap(/* safe because of the length */);
         for max in 1..=k {
-            let gens: Vec<usize> = generators.iter().cloned().collect();
-            let sum_iterator = Words::new(generators.len() - 1, k - max)
-                .map(move |mut word| {
-                    word.push(max);
-                    word
-                })
-                .map(move |word| {
-                    word.into_iter()
-                        .zip(gens.iter())
-                        .map(|(l, r)| l * r)
-                        .sum::<usize>()
-                        % modulus
-                });
-            iterator = Box::new(iterator.chain(sum_iterator));
+            let mut words = Words::new(others.len(), k - max);
+            while let Option::Some(word) = words.advance() {
+                let sum = word.iter().zip(others).map(|(l, r)| l * r).sum::<usize>() + max * last;
+                visit(sum % modulus)?;
+            }
         }
+        ControlFlow::Continue(())
     } else {
-        iterator = Box::new(once((k * generators[0]) % modulus));
+        visit((k * generators[0]) % modulus)
     }
-    iterator
 }
 
 fn is_subsumfree(k: usize, modulus: usize, residues: &[usize]) -> bool {
-    for word in Words::new(residues.len(), k) {
-        let residue = residues
-            .iter()
-            .zip(&word)
-            .map(|(l, r)| l * r)
-            .sum::<usize>()
-            % modulus;
+    let mut words = Words::new(residues.len(), k);
+    while let Option::Some(word) = words.advance() {
+        let residue = residues.iter().zip(word).map(|(l, r)| l * r).sum::<usize>() % modulus;
         if residues.contains(&residue) {
             return false;
         }
@@ -0,0 +1,173 @@
+//! The `error` module describes what can go wrong when constructing
+//! sequences and combinatorial iterators.
+
+use std::fmt::Display;
+
+/// The errors that occur when a sequence or an iterator is constructed with
+/// invalid parameters.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Error {
+    /// The initial segment has no elements.
+    EmptyInitialSegment,
+    /// The initial segment decreases at `index`.
+    Unsorted {
+        /// The index of the offending element.
+        index: usize,
+        /// The element that precedes the offending element.
+        previous: usize,
+        /// The offending element.
+        element: usize,
+    },
+    /// The initial segment contains `element` more than once.
+    Duplicated {
+        /// The index of the second occurrence.
+        index: usize,
+        /// The duplicated element.
+        element: usize,
+    },
+    /// The initial segment contains an element that is expressable by
+    /// earlier elements, i.e. the seed violates the subsumfree property.
+    Expressable {
+        /// The index of the offending element.
+        index: usize,
+        /// The offending element.
+        element: usize,
+    },
+    /// More elements are chosen than there are available.
+    TooManyChosen {
+        /// The number of available elements.
+        n: usize,
+        /// The number of chosen elements.
+        t: usize,
+    },
+    /// A word without letters can not have a positive weight.
+    EmptyWord {
+        /// The requested weight.
+        weight: usize,
+    },
+    /// A partition can not have a part equal to zero.
+    ZeroPart,
+    /// Every element must be allowed at least once in an expression.
+    ZeroMultiplicity,
+    /// An expression needs at least one summand.
+    ZeroSummands,
+    /// The coefficients of a linear form must be positive.
+    ZeroCoefficient,
+    /// A point of the initial segment differs in dimension from the first.
+    Dimension {
+        /// The index of the offending point.
+        index: usize,
+        /// The dimension of the first point.
+        expected: usize,
+        /// The dimension of the offending point.
+        actual: usize,
+    },
+    /// A point of the initial segment does not follow its predecessor in the
+    /// order of the points.
+    UnsortedPoint {
+        /// The index of the offending point.
+        index: usize,
+    },
+    /// A point of the initial segment is expressable by earlier points.
+    ExpressablePoint {
+        /// The index of the offending point.
+        index: usize,
+    },
+    /// A number can not be both excluded from and forced into a sequence.
+    ExcludedAndForced {
+        /// The offending number.
+        element: usize,
+    },
+    /// The initial segment contains an excluded number.
+    ExcludedInitial {
+        /// The index of the offending element.
+        index: usize,
+        /// The offending element.
+        element: usize,
+    },
+    /// A forced number does not follow the initial segment, so it can not be
+    /// put in the sequence.
+    ForcedInitial {
+        /// The offending number.
+        element: usize,
+        /// The last element of the initial segment.
+        last: usize,
+    },
+    /// The cache of generated sequences can not be read or written.
+    Cache {
+        /// Why the cache failed.
+        reason: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::EmptyInitialSegment => write!(f, "the initial segment is empty"),
+            Error::Unsorted {
+                index,
+                previous,
+                element,
+            } => write!(
+                f,
+                "the initial segment is not increasing: {} at index {} follows {}",
+                element, index, previous
+            ),
+            Error::Duplicated { index, element } => write!(
+                f,
+                "the initial segment contains {} more than once (again at index {})",
+                element, index
+            ),
+            Error::Expressable { index, element } => write!(
+                f,
+                "the initial segment is not subsumfree: {} at index {} is expressable by earlier elements",
+                element, index
+            ),
+            Error::TooManyChosen { n, t } => {
+                write!(f, "can not choose {} out of {} elements", t, n)
+            }
+            Error::EmptyWord { weight } => {
+                write!(f, "a word of length 0 can not have weight {}", weight)
+            }
+            Error::ZeroPart => write!(f, "a partition can not have a part equal to 0"),
+            Error::ZeroMultiplicity => write!(f, "the multiplicity must be at least 1"),
+            Error::ZeroSummands => write!(f, "the number of summands must be at least 1"),
+            Error::ZeroCoefficient => write!(f, "the coefficients must be at least 1"),
+            Error::Dimension {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "the point at index {} has dimension {} instead of {}",
+                index, actual, expected
+            ),
+            Error::UnsortedPoint { index } => write!(
+                f,
+                "the initial segment is not increasing: the point at index {} does not follow its predecessor",
+                index
+            ),
+            Error::ExcludedAndForced { element } => {
+                write!(f, "{} is both excluded and forced", element)
+            }
+            Error::ExcludedInitial { index, element } => write!(
+                f,
+                "the initial segment contains the excluded number {} at index {}",
+                element, index
+            ),
+            Error::ForcedInitial { element, last } => write!(
+                f,
+                "the forced number {} does not follow the initial segment, which ends with {}",
+                element, last
+            ),
+            Error::Cache { reason } => write!(f, "the cache failed: {}", reason),
+            Error::ExpressablePoint { index } => write!(
+                f,
+                "the initial segment is not subsumfree: the point at index {} is expressable by earlier points",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
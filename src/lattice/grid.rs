@@ -0,0 +1,220 @@
+//! A finite window on a lattice point set.
+
+use std::io::{Error, ErrorKind, Write};
+
+use crate::tools::period::Info;
+
+/// The membership of the points in the box `[0, bounds[0]) × … × [0, bounds[d-1])`.
+///
+/// ```
+/// # use sequence::lattice::Grid;
+/// let grid = Grid::new(vec![3, 2], &[vec![0, 1], vec![2, 0]]);
+/// assert!(grid.contains(&[2, 0]));
+/// assert!(!grid.contains(&[1, 1]));
+/// assert!(!grid.contains(&[3, 0]));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Grid {
+    bounds: Vec<usize>,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    /// Creates a `Grid` of the `points` within `bounds`. Points outside the
+    /// box are ignored.
+    pub fn new(bounds: Vec<usize>, points: &[Vec<usize>]) -> Self {
+        let mut grid = Self {
+            cells: vec![false; bounds.iter().product()],
+            bounds,
+        };
+        for point in points {
+            if let Option::Some(index) = grid.index(point) {
+                grid.cells[index] = true;
+            }
+        }
+        grid
+    }
+
+    /// The bounds of the box.
+    pub fn bounds(&self) -> &[usize] {
+        &self.bounds
+    }
+
+    /// Whether `point` lies in the box and belongs to the set.
+    pub fn contains(&self, point: &[usize]) -> bool {
+        self.index(point)
+            .map(|index| self.cells[index])
+            .unwrap_or(false)
+    }
+
+    fn index(&self, point: &[usize]) -> Option<usize> {
+        if point.len() != self.bounds.len() {
+            return Option::None;
+        }
+        let mut index = 0;
+        for (coordinate, bound) in point.iter().zip(&self.bounds) {
+            if coordinate >= bound {
+                return Option::None;
+            }
+            index = index * bound + coordinate;
+        }
+        Option::Some(index)
+    }
+
+    fn point(&self, mut index: usize) -> Vec<usize> {
+        let mut point = vec![0; self.bounds.len()];
+        for (coordinate, bound) in point.iter_mut().zip(&self.bounds).rev() {
+            *coordinate = index % bound;
+            index /= bound;
+        }
+        point
+    }
+
+    /// Try to find the `Info` of the set along `axis`.
+    ///
+    /// The set has pre-period `p` and period `q` along the axis when a point
+    /// with coordinate at least `p` belongs to the set exactly when the point
+    /// `q` further along the axis does, as far as the box reaches.
+    ///
+    /// ```
+    /// # use sequence::lattice::Grid;
+    /// # use sequence::tools::period::Info;
+    /// let points: Vec<Vec<usize>> = (0..10).step_by(3).map(|x| vec![x, 0]).collect();
+    /// let grid = Grid::new(vec![10, 1], &points);
+    /// assert_eq!(grid.period(0), Some(Info::new(0, 3)));
+    /// ```
+    pub fn period(&self, axis: usize) -> Option<Info> {
+        let bound = self.bounds[axis];
+        // a pre-period below half the box and a period of at most half the
+        // box leave at least one pair of points to compare
+        for pre_period in 0..(bound / 2) {
+            for period in 1..=(bound / 2) {
+                if self.is_periodic(axis, pre_period, period) {
+                    return Option::Some(Info::new(pre_period, period));
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn is_periodic(&self, axis: usize, pre_period: usize, period: usize) -> bool {
+        (0..self.cells.len()).all(|index| {
+            let mut point = self.point(index);
+            if point[axis] < pre_period || point[axis] + period >= self.bounds[axis] {
+                return true;
+            }
+            point[axis] += period;
+            self.cells[index] == self.contains(&point)
+        })
+    }
+
+    fn plane(&self) -> Result<(usize, usize), Error> {
+        match self.bounds.as_slice() {
+            [width, height] => Ok((*width, *height)),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "only two-dimensional grids can be drawn",
+            )),
+        }
+    }
+
+    /// Write the set as a binary PPM image, with the first axis to the right
+    /// and the second axis upwards. Points of the set are black.
+    pub fn write_ppm<T>(&self, f: &mut T) -> Result<(), Error>
+    where
+        T: Write,
+    {
+        let (width, height) = self.plane()?;
+        write!(f, "P6\n{} {}\n255\n", width, height)?;
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let value = if self.contains(&[x, y]) { 0 } else { 255 };
+                f.write_all(&[value; 3])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the set as an SVG image, with the first axis to the right and
+    /// the second axis upwards. Points of the set are black unit squares.
+    pub fn write_svg<T>(&self, f: &mut T) -> Result<(), Error>
+    where
+        T: Write,
+    {
+        let (width, height) = self.plane()?;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {0} {1}\" width=\"{2}\" height=\"{3}\">",
+            width,
+            height,
+            4 * width,
+            4 * height
+        )?;
+        writeln!(
+            f,
+            "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+            width, height
+        )?;
+        for y in 0..height {
+            for x in 0..width {
+                if self.contains(&[x, y]) {
+                    writeln!(
+                        f,
+                        "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\"/>",
+                        x,
+                        height - 1 - y
+                    )?;
+                }
+            }
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn period_respects_the_pre_period() {
+        let points: Vec<Vec<usize>> = [0, 2, 5, 7, 9, 11, 13, 15, 17, 19]
+            .iter()
+            .flat_map(|x| (0..3).map(move |y| vec![y, *x]))
+            .collect();
+        let grid = Grid::new(vec![3, 20], &points);
+
+        assert_eq!(grid.period(1), Some(Info::new(4, 2)));
+        assert_eq!(grid.period(0), Some(Info::new(0, 1)));
+    }
+
+    #[test]
+    fn period_needs_two_points_along_the_axis() {
+        let points: Vec<Vec<usize>> = vec![vec![0, 0], vec![0, 1], vec![3, 0], vec![3, 1]];
+        let grid = Grid::new(vec![4, 2], &points);
+
+        assert_eq!(grid.period(0), None);
+        assert_eq!(grid.period(1), Some(Info::new(0, 1)));
+        assert_eq!(Grid::new(vec![1, 2], &[vec![0, 1]]).period(0), None);
+        assert_eq!(Grid::new(vec![2, 2], &[]).period(1), Some(Info::new(0, 1)));
+    }
+
+    #[test]
+    fn ppm_has_one_pixel_per_point() {
+        let grid = Grid::new(vec![2, 2], &[vec![0, 1]]);
+        let mut output: Vec<u8> = Vec::new();
+        grid.write_ppm(&mut output).unwrap();
+
+        assert_eq!(output[..11], *b"P6\n2 2\n255\n");
+        assert_eq!(
+            output[11..],
+            [0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn images_need_two_dimensions() {
+        let grid = Grid::new(vec![2, 2, 2], &[]);
+
+        assert!(grid.write_svg(&mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+//! The `lattice` module explores the analogue of subsumfree sequences for
+//! lattice points in `N^d`.
+//!
+//! Points are added greedily in a fixed [`Order`] when they are not the sum of
+//! `k` distinct earlier points.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::Config;
+//! # use sequence::lattice::{Order, Sequence};
+//! let initial = vec![vec![0, 1], vec![1, 0]];
+//! let config = Config::new().ceiling(3);
+//! let actual: Vec<Vec<usize>> =
+//!     Sequence::with_config(initial, Order::GradedLexicographic, config).collect();
+//! let expected = vec![vec![0, 1], vec![1, 0], vec![0, 2], vec![2, 0]];
+//! assert_eq!(actual, expected);
+//! ```
+
+mod grid;
+
+pub use grid::Grid;
+
+use std::cmp::Ordering;
+
+use crate::budget::{Budgeted, Counters, Exhaustion, Instrumented, Meter};
+use crate::combinatorics::Words;
+use crate::combinatorics::combination::Indices;
+use crate::{Config, Error};
+
+/// A lattice point in `N^d`.
+pub type Point = Vec<usize>;
+
+/// The order in which lattice points are considered.
+///
+/// Both orders first compare the degree, i.e. the sum of the coordinates, so
+/// that every point is preceded by finitely many points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Order {
+    /// Points of equal degree are compared lexicographically.
+    #[default]
+    GradedLexicographic,
+    /// Points of equal degree are compared by their last differing
+    /// coordinate, where the larger coordinate comes first.
+    GradedReverseLexicographic,
+}
+
+impl Order {
+    /// Compares two points of the same dimension.
+    ///
+    /// ```
+    /// # use std::cmp::Ordering;
+    /// # use sequence::lattice::Order;
+    /// assert_eq!(Order::GradedLexicographic.compare(&[0, 2], &[1, 1]), Ordering::Less);
+    /// assert_eq!(Order::GradedLexicographic.compare(&[2, 0], &[0, 3]), Ordering::Less);
+    /// ```
+    pub fn compare(&self, left: &[usize], right: &[usize]) -> Ordering {
+        let degree = degree(left).cmp(&degree(right));
+        if degree != Ordering::Equal {
+            return degree;
+        }
+        match self {
+            Order::GradedLexicographic => left.cmp(right),
+            Order::GradedReverseLexicographic => left
+                .iter()
+                .zip(right)
+                .rev()
+                .find(|(l, r)| l != r)
+                .map(|(l, r)| r.cmp(l))
+                .unwrap_or(Ordering::Equal),
+        }
+    }
+}
+
+/// The degree of a point, i.e. the sum of its coordinates.
+pub fn degree(point: &[usize]) -> usize {
+    point.iter().sum()
+}
+
+/// Iterates over all points of a dimension in an [`Order`].
+///
+/// ```
+/// # use sequence::lattice::{Order, Points};
+/// let actual: Vec<Vec<usize>> = Points::new(2, Order::GradedLexicographic).take(4).collect();
+/// let expected = vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![0, 2]];
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Debug)]
+pub struct Points {
+    dimension: usize,
+    order: Order,
+    degree: usize,
+    pending: Vec<Point>,
+}
+
+impl Points {
+    /// Creates an iterator over the points of `dimension` in `order`.
+    pub fn new(dimension: usize, order: Order) -> Self {
+        Self {
+            dimension,
+            order,
+            degree: 0,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Iterator for Points {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            if self.dimension == 0 && self.degree > 0 {
+                return Option::None;
+            }
+            self.pending = Words::new(self.dimension, self.degree).collect();
+            // pop from the back, so the largest point goes first
+            self.pending
+                .sort_by(|left, right| self.order.compare(right, left));
+            self.degree += 1;
+        }
+        self.pending.pop()
+    }
+}
+
+/// Determines if `point` is a sum of `t` distinct points from `prefix`.
+fn is_sum(prefix: &[Point], t: usize, point: &[usize]) -> bool {
+    let below: Vec<&Point> = prefix
+        .iter()
+        .filter(|other| other.iter().zip(point).all(|(o, p)| o <= p))
+        .collect();
+    let mut summands = Indices::new(below.len(), t);
+    while let Option::Some(indices) = summands.advance() {
+        if (0..point.len()).all(|axis| {
+            indices
+                .iter()
+                .map(|index| below[*index][axis])
+                .sum::<usize>()
+                == point[axis]
+        }) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Greedy lattice point sets without sums of `k` distinct earlier points.
+///
+/// The number of summands and the ceiling come from a [`Config`], where the
+/// ceiling bounds the degree of the points.
+#[derive(Debug)]
+pub struct Sequence {
+    order: Order,
+    counts: Vec<usize>,
+    ceiling: Option<usize>,
+    initial: std::vec::IntoIter<Point>,
+    elements: Vec<Point>,
+    candidates: Points,
+    meter: Meter,
+}
+
+impl Sequence {
+    /// Creates a `Sequence` with an initial segment of points.
+    ///
+    /// # Panics
+    /// Panics when the initial segment is invalid, see [`Sequence::try_new`].
+    pub fn new(initial: Vec<Point>, order: Order) -> Self {
+        Self::try_new(initial, order).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an initial segment of points, failing when
+    /// the initial segment is empty, its points differ in dimension, are not
+    /// strictly increasing in `order` or are sums of earlier points.
+    pub fn try_new(initial: Vec<Point>, order: Order) -> Result<Self, Error> {
+        Self::try_with_config(initial, order, Config::new())
+    }
+
+    /// Creates a `Sequence` with an initial segment of points and a
+    /// [`Config`].
+    ///
+    /// # Panics
+    /// Panics when the initial segment or the configuration is invalid, see
+    /// [`Sequence::try_with_config`].
+    pub fn with_config(initial: Vec<Point>, order: Order, config: Config) -> Self {
+        Self::try_with_config(initial, order, config).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a `Sequence` with an initial segment of points and a
+    /// [`Config`], failing when the initial segment is invalid or when the
+    /// configuration asks for zero summands.
+    pub fn try_with_config(
+        initial: Vec<Point>,
+        order: Order,
+        config: Config,
+    ) -> Result<Self, Error> {
+        if initial.is_empty() {
+            return Err(Error::EmptyInitialSegment);
+        }
+        config.verify_options(&initial)?;
+        let counts = config.counts(&initial);
+        let dimension = initial[0].len();
+        for (index, point) in initial.iter().enumerate() {
+            if point.len() != dimension {
+                return Err(Error::Dimension {
+                    index,
+                    expected: dimension,
+                    actual: point.len(),
+                });
+            }
+            if index > 0 && order.compare(&initial[index - 1], point) != Ordering::Less {
+                return Err(Error::UnsortedPoint { index });
+            }
+            if counts.iter().any(|t| is_sum(&initial[..index], *t, point)) {
+                return Err(Error::ExpressablePoint { index });
+            }
+        }
+        let last = initial[initial.len() - 1].clone();
+        let mut candidates = Points::new(dimension, order);
+        for point in candidates.by_ref() {
+            if point == last {
+                break;
+            }
+        }
+        Ok(Self {
+            order,
+            counts,
+            ceiling: config.maximum(),
+            initial: initial.into_iter(),
+            elements: Vec::new(),
+            candidates,
+            meter: Meter::new(&config),
+        })
+    }
+
+    /// The order in which the points are added.
+    pub fn order(&self) -> Order {
+        self.order
+    }
+}
+
+impl Iterator for Sequence {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Option::Some(point) = self.initial.next() {
+            self.elements.push(point.clone());
+            return Option::Some(point);
+        }
+        for point in self.candidates.by_ref() {
+            if self
+                .ceiling
+                .map(|ceiling| degree(&point) >= ceiling)
+                .unwrap_or(false)
+            {
+                return Option::None;
+            }
+            if !self.meter.examine_next(self.elements.len()) {
+                return Option::None;
+            }
+            if !self
+                .counts
+                .iter()
+                .any(|t| is_sum(&self.elements, *t, &point))
+            {
+                self.elements.push(point.clone());
+                return Option::Some(point);
+            }
+        }
+        Option::None
+    }
+}
+
+impl Budgeted for Sequence {
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.meter.exhausted()
+    }
+}
+
+impl Instrumented for Sequence {
+    fn counters(&self) -> Counters {
+        self.meter.counters(self.elements.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_follow_the_order() {
+        for order in [
+            Order::GradedLexicographic,
+            Order::GradedReverseLexicographic,
+        ] {
+            let points: Vec<Point> = Points::new(3, order).take(50).collect();
+            for pair in points.windows(2) {
+                assert_eq!(order.compare(&pair[0], &pair[1]), Ordering::Less);
+            }
+        }
+    }
+
+    #[test]
+    fn one_dimensional_sequence_avoids_sums_of_distinct_points() {
+        let initial = vec![vec![1], vec![2], vec![3]];
+        let actual: Vec<usize> =
+            Sequence::with_config(initial, Order::default(), Config::new().ceiling(60))
+                .map(|point| point[0])
+                .collect();
+        let expected: Vec<usize> = vec![1, 2, 3, 4, 5, 13, 14, 15, 25, 26, 27, 37, 38, 48, 49, 50];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sequence_rejects_invalid_initial_segments() {
+        let order = Order::GradedLexicographic;
+        assert_eq!(
+            Sequence::try_new(vec![vec![1, 0], vec![1]], order).err(),
+            Some(Error::Dimension {
+                index: 1,
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            Sequence::try_new(vec![vec![1, 0], vec![0, 1]], order).err(),
+            Some(Error::UnsortedPoint { index: 1 })
+        );
+        let initial = vec![vec![0, 1], vec![1, 0], vec![1, 1]];
+        assert_eq!(
+            Sequence::try_with_config(initial, order, Config::new().summands([2])).err(),
+            Some(Error::ExpressablePoint { index: 2 })
+        );
+    }
+}
@@ -2,22 +2,77 @@
 
 //! The `sequence` library allows one to examine _subsumfree_ sequences.
 
+pub mod analysis;
+pub mod budget;
+pub mod cache;
+pub mod cli;
 pub mod combinatorics;
+pub mod config;
 pub mod density;
+pub mod error;
+pub mod lattice;
+pub mod plot;
 pub mod tools;
 
+pub use budget::Generator;
+pub use config::Config;
+pub use error::Error;
+
+use combinatorics::Product;
 use combinatorics::combination::Sequence as CombinationSequence;
+use combinatorics::form::Sequence as LinearSequence;
+use combinatorics::word::BoundedSequence;
 use combinatorics::word::Sequence as ExpressionSequence;
+use combinatorics::xor::Sequence as XorSequence;
+
+/// How often an element may occur in an expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Mode {
+    /// Every element occurs at most once, i.e. the summands are distinct.
+    Distinct,
+    /// Every element occurs arbitrarily often.
+    Duplicate,
+    /// Every element occurs at most the given number of times.
+    Bounded(usize),
+    /// Every element occurs arbitrarily often, weighted by the coefficients
+    /// of a linear form.
+    Linear(Vec<usize>),
+    /// Every element occurs at most once and addition is replaced by XOR.
+    Xor,
+    /// Every element occurs at most once and addition is replaced by
+    /// multiplication.
+    Product,
+}
 
-/// Returns an iterator with iterates over a subsumfree sequence.
+/// Returns an iterator with iterates over a subsumfree sequence, bounded by
+/// the [`Budget`](budget::Budget) of the configuration.
+///
+/// Fails when the initial segment or the configuration is invalid.
 pub fn sequence(
     initial: Vec<usize>,
-    ceiling: usize,
-    duplicate: bool,
-) -> Box<dyn Iterator<Item = usize>> {
-    if duplicate {
-        Box::new(ExpressionSequence::with_maximum(initial, ceiling))
-    } else {
-        Box::new(CombinationSequence::with_maximum(initial, ceiling))
+    mode: Mode,
+    config: Config,
+) -> Result<Box<dyn Generator>, Error> {
+    match mode {
+        Mode::Distinct => Ok(Box::new(CombinationSequence::try_with_config(
+            initial, config,
+        )?)),
+        Mode::Duplicate => Ok(Box::new(ExpressionSequence::try_with_config(
+            initial, config,
+        )?)),
+        Mode::Bounded(multiplicity) => Ok(Box::new(BoundedSequence::try_with_config(
+            initial,
+            multiplicity,
+            config,
+        )?)),
+        Mode::Linear(coefficients) => Ok(Box::new(LinearSequence::try_with_config(
+            initial,
+            coefficients,
+            config,
+        )?)),
+        Mode::Xor => Ok(Box::new(XorSequence::try_with_config(initial, config)?)),
+        Mode::Product => Ok(Box::new(CombinationSequence::try_with_operation(
+            initial, Product, config,
+        )?)),
     }
 }
@@ -1,23 +1,252 @@
-use clap::Parser;
-use sequence::sequence;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{Parser, Subcommand};
+use sequence::analysis::inverse::Search;
+use sequence::analysis::{Row, Rows, asymptotic_density};
+use sequence::budget::Budgeted;
+use sequence::cli::{Arguments, Jobs};
+use sequence::combinatorics::form::LinearForm;
+use sequence::plot::{barcode_chart, density_chart, differences_chart, write_svg};
+use sequence::tools::bfile::{BFile, Comparison};
+use sequence::tools::differences;
+use sequence::tools::period::detect_cycle;
+use sequence::tools::recurrence::{generating_function, guess_recurrence};
+use sequence::tools::residue::detect_residues;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Input {
-    #[arg(short, long, default_value_t = 500)]
-    length: usize,
-    #[arg(short, long, default_value_t = 1000)]
-    ceiling: usize,
-    #[arg(short, long, default_value_t = false)]
-    duplicate: bool,
-    initial: Vec<usize>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    arguments: Arguments,
+    /// Read one job per line from a file, or from standard input for `-`,
+    /// and write one record per job.
+    #[arg(short, long)]
+    batch: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print growth statistics of the sequence as a table.
+    Stats {
+        #[command(flatten)]
+        arguments: Arguments,
+        /// The distance between the rows of the table. Defaults to a tenth of
+        /// the ceiling.
+        #[arg(short, long)]
+        step: Option<usize>,
+    },
+    /// Plot the differences, the counting function and the residues of the
+    /// sequence as an SVG image.
+    Plot {
+        #[command(flatten)]
+        arguments: Arguments,
+        /// The modulus of the residues. Defaults to the sum of the
+        /// differences in the period, or 10 without a period.
+        #[arg(long)]
+        modulus: Option<usize>,
+        /// The file to write the image to, instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare the sequence with an OEIS b-file and report the first index
+    /// where they disagree. Exits with a failure when they do.
+    Compare {
+        #[command(flatten)]
+        arguments: Arguments,
+        /// The b-file to compare with.
+        #[arg(short, long)]
+        bfile: PathBuf,
+    },
+    /// Guess a linear recurrence, the rational generating function and the
+    /// periodic residues of the sequence.
+    Guess {
+        #[command(flatten)]
+        arguments: Arguments,
+        /// The largest modulus to search for periodic residues.
+        #[arg(long, default_value_t = 100)]
+        moduli: usize,
+    },
+    /// Search the initial segments, modes and numbers of summands that
+    /// generate the given numbers.
+    Infer {
+        /// The largest number of summands to try.
+        #[arg(short, long, default_value_t = 5)]
+        k: usize,
+        /// The numbers, in increasing order.
+        #[arg(required = true)]
+        numbers: Vec<usize>,
+    },
 }
 
 fn main() {
     let input = Input::parse();
 
-    let iterator: Box<dyn Iterator<Item = usize>> =
-        sequence(input.initial, input.ceiling, input.duplicate);
-    let seq: Vec<usize> = iterator.take(input.length).collect();
+    match input.command {
+        Option::Some(Command::Stats { arguments, step }) => stats(&arguments, step),
+        Option::Some(Command::Plot {
+            arguments,
+            modulus,
+            output,
+        }) => plot(&arguments, modulus, output),
+        Option::Some(Command::Compare { arguments, bfile }) => compare(&arguments, &bfile),
+        Option::Some(Command::Guess { arguments, moduli }) => guess(&arguments, moduli),
+        Option::Some(Command::Infer { k, numbers }) => infer(k, &numbers),
+        Option::None => match &input.batch {
+            Option::Some(path) => batch(path),
+            Option::None => list(&input.arguments),
+        },
+    }
+}
+
+fn list(arguments: &Arguments) {
+    let seq = generate(arguments);
 
     println!("{} {:?}", seq.len(), seq);
 }
+
+/// Generates the sequence, exiting on errors and warning about partial
+/// results.
+fn generate(arguments: &Arguments) -> Vec<usize> {
+    match arguments.generate() {
+        Ok(generated) => {
+            if let Option::Some(warning) = generated.warning() {
+                eprintln!("warning: {}", warning);
+            }
+            generated.elements
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn batch(path: &str) {
+    Jobs::<Arguments>::run(path, |arguments| {
+        let generated = arguments.generate().map_err(|error| error.to_string())?;
+        let seq = &generated.elements;
+        Ok((format!("{} {:?}", seq.len(), seq), generated.warning()))
+    });
+}
+
+fn stats(arguments: &Arguments, step: Option<usize>) {
+    let mut iterator = match arguments.iterator() {
+        Ok(iterator) => iterator,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    let step = step.unwrap_or(arguments.ceiling / 10).max(1);
+
+    // keep the first elements to determine the character afterwards
+    let mut seq: Vec<usize> = Vec::new();
+    let elements = iterator.by_ref().inspect(|element| {
+        if seq.len() < arguments.length {
+            seq.push(*element);
+        }
+    });
+    println!("{}", Row::header());
+    for row in Rows::new(elements, step, arguments.ceiling) {
+        println!("{}", row);
+    }
+    if let Option::Some(exhausted) = iterator.exhausted() {
+        eprintln!("warning: the rows are incomplete: {}", exhausted);
+    }
+
+    let form = arguments.form().unwrap_or(LinearForm::distinct(3));
+    match arguments.character(&seq, form) {
+        Option::Some(character) => println!(
+            "\nasymptotic density {:.6} ({})",
+            asymptotic_density(&character),
+            character
+        ),
+        Option::None => println!("\nasymptotic density ?"),
+    }
+}
+
+fn plot(arguments: &Arguments, modulus: Option<usize>, output: Option<PathBuf>) {
+    let seq = generate(arguments);
+
+    let differences = differences(&seq);
+    let info = detect_cycle(&differences).filter(|info| info.check(&differences));
+    let modulus = modulus
+        .or_else(|| {
+            info.as_ref().map(|info| {
+                differences[info.pre_period..(info.pre_period + info.period)]
+                    .iter()
+                    .sum()
+            })
+        })
+        .unwrap_or(10)
+        .max(1);
+    let charts = [
+        differences_chart(&seq, info.as_ref()),
+        density_chart(&seq),
+        barcode_chart(&seq, modulus, info.as_ref()),
+    ];
+
+    let written = match &output {
+        Option::Some(path) => File::create(path).and_then(|mut file| write_svg(&charts, &mut file)),
+        Option::None => write_svg(&charts, &mut io::stdout()),
+    };
+    if let Err(error) = written {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn compare(arguments: &Arguments, path: &Path) {
+    let bfile = match BFile::open(path) {
+        Ok(bfile) => bfile,
+        Err(error) => {
+            eprintln!("error: {}: {}", path.display(), error);
+            process::exit(1);
+        }
+    };
+    let seq = generate(arguments);
+
+    let comparison = bfile.compare(&seq);
+    println!("{}", comparison);
+    if let Comparison::Disagree { .. } = comparison {
+        process::exit(1);
+    }
+}
+
+fn guess(arguments: &Arguments, moduli: usize) {
+    let seq = generate(arguments);
+
+    match guess_recurrence(&seq) {
+        Option::Some(recurrence) => println!("recurrence: {}", recurrence),
+        Option::None => println!("recurrence: ?"),
+    }
+    // the linear form only matters for Walnut, not for the generating function
+    let form = arguments.form().unwrap_or(LinearForm::distinct(3));
+    match arguments
+        .character(&seq, form)
+        .and_then(|character| generating_function(&seq, &character))
+    {
+        Option::Some(gf) => println!("generating function: {}", gf),
+        Option::None => println!("generating function: ?"),
+    }
+    match detect_residues(&seq, moduli) {
+        Option::Some(residues) => println!("residues: {}", residues),
+        Option::None => println!("residues: ?"),
+    }
+}
+
+fn infer(k: usize, numbers: &[usize]) {
+    let candidates = Search::new().summands(1..=k).infer(numbers);
+    if candidates.is_empty() {
+        eprintln!("error: no parameters generate the numbers");
+        process::exit(1);
+    }
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+}
@@ -0,0 +1,318 @@
+//! The `plot` module renders sequences as SVG charts without any dependencies.
+//!
+//! A chart plots a series of points, either connected or as dots, and can
+//! shade ranges of the horizontal axis, e.g. the pre-period and the period
+//! that [`period::Info`](crate::tools::period::Info) describes.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::plot::{Chart, write_svg};
+//! let chart = Chart::line("squares", (0..10).map(|n| (n as f64, (n * n) as f64)).collect())
+//!     .shade(2.0, 4.0, "#dddddd");
+//! let mut output: Vec<u8> = Vec::new();
+//! write_svg(&[chart], &mut output).unwrap();
+//! assert!(String::from_utf8(output).unwrap().starts_with("<svg"));
+//! ```
+
+use std::io::{Error, Write};
+
+use crate::tools::differences;
+use crate::tools::period::Info;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 200.0;
+const MARGIN: f64 = 40.0;
+
+/// The color of a shaded pre-period.
+pub const PRE_PERIOD: &str = "#eeeeee";
+/// The color of a shaded period.
+pub const PERIOD: &str = "#d6e6f5";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Line,
+    Dots,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Shade {
+    from: f64,
+    to: f64,
+    color: String,
+}
+
+/// A chart of a series of points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    title: String,
+    points: Vec<(f64, f64)>,
+    style: Style,
+    shades: Vec<Shade>,
+}
+
+impl Chart {
+    /// Creates a chart that connects the points.
+    pub fn line(title: &str, points: Vec<(f64, f64)>) -> Self {
+        Self::new(title, points, Style::Line)
+    }
+
+    /// Creates a chart that draws the points as dots.
+    pub fn dots(title: &str, points: Vec<(f64, f64)>) -> Self {
+        Self::new(title, points, Style::Dots)
+    }
+
+    fn new(title: &str, points: Vec<(f64, f64)>, style: Style) -> Self {
+        Self {
+            title: title.to_string(),
+            points,
+            style,
+            shades: Vec::new(),
+        }
+    }
+
+    /// Shades the horizontal range from `from` to `to` in `color`.
+    pub fn shade(mut self, from: f64, to: f64, color: &str) -> Self {
+        self.shades.push(Shade {
+            from,
+            to,
+            color: color.to_string(),
+        });
+        self
+    }
+
+    /// Shades the pre-period and the first period of `info`, where the
+    /// horizontal axis counts indices.
+    pub fn shade_period(self, info: &Info) -> Self {
+        let start = info.pre_period as f64;
+        let end = (info.pre_period + info.period) as f64;
+        self.shade(0.0, start, PRE_PERIOD).shade(start, end, PERIOD)
+    }
+
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        let xs = self
+            .points
+            .iter()
+            .map(|(x, _)| *x)
+            .chain(self.shades.iter().flat_map(|shade| [shade.from, shade.to]));
+        let (x_min, x_max) = extremes(xs);
+        let (y_min, y_max) = extremes(self.points.iter().map(|(_, y)| *y).chain([0.0]));
+        (x_min, x_max, y_min, y_max)
+    }
+
+    fn write<T>(&self, top: f64, f: &mut T) -> Result<(), Error>
+    where
+        T: Write,
+    {
+        let (x_min, x_max, y_min, y_max) = self.bounds();
+        let x = |value: f64| MARGIN + (value - x_min) / (x_max - x_min) * (WIDTH - 2.0 * MARGIN);
+        let y = |value: f64| {
+            top + HEIGHT - MARGIN + (y_min - value) / (y_max - y_min) * (HEIGHT - 2.0 * MARGIN)
+        };
+
+        writeln!(
+            f,
+            "<text x=\"{}\" y=\"{}\" font-size=\"14\">{}</text>",
+            MARGIN,
+            top + MARGIN / 2.0,
+            escape(&self.title)
+        )?;
+        for shade in &self.shades {
+            writeln!(
+                f,
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>",
+                x(shade.from),
+                y(y_max),
+                x(shade.to) - x(shade.from),
+                y(y_min) - y(y_max),
+                escape(&shade.color)
+            )?;
+        }
+        writeln!(
+            f,
+            "<polyline points=\"{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}\" fill=\"none\" stroke=\"black\"/>",
+            x(x_min),
+            y(y_max),
+            x(x_min),
+            y(y_min),
+            x(x_max),
+            y(y_min)
+        )?;
+        for value in [y_min, y_max] {
+            writeln!(
+                f,
+                "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"10\" text-anchor=\"end\">{}</text>",
+                MARGIN - 4.0,
+                y(value),
+                label(value)
+            )?;
+        }
+        for value in [x_min, x_max] {
+            writeln!(
+                f,
+                "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"10\" text-anchor=\"middle\">{}</text>",
+                x(value),
+                y(y_min) + 14.0,
+                label(value)
+            )?;
+        }
+        match self.style {
+            Style::Line => {
+                let points: Vec<String> = self
+                    .points
+                    .iter()
+                    .map(|(px, py)| format!("{:.2},{:.2}", x(*px), y(*py)))
+                    .collect();
+                writeln!(
+                    f,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\"/>",
+                    points.join(" ")
+                )
+            }
+            Style::Dots => {
+                for (px, py) in &self.points {
+                    writeln!(
+                        f,
+                        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"1.5\" fill=\"steelblue\"/>",
+                        x(*px),
+                        y(*py)
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The smallest and largest value, widened when they coincide so that they
+/// span a range.
+fn extremes<I>(values: I) -> (f64, f64)
+where
+    I: Iterator<Item = f64>,
+{
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min, min + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+fn label(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.3}", value)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write the charts as a single SVG image, stacked from top to bottom.
+pub fn write_svg<T>(charts: &[Chart], f: &mut T) -> Result<(), Error>
+where
+    T: Write,
+{
+    let height = HEIGHT * charts.len() as f64;
+    writeln!(
+        f,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">",
+        WIDTH, height, WIDTH, height
+    )?;
+    writeln!(
+        f,
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        WIDTH, height
+    )?;
+    for (index, chart) in charts.iter().enumerate() {
+        chart.write(HEIGHT * index as f64, f)?;
+    }
+    writeln!(f, "</svg>")
+}
+
+/// The differences of consecutive elements versus their index, with the
+/// period of the differences shaded when available.
+pub fn differences_chart(seq: &[usize], info: Option<&Info>) -> Chart {
+    let points = differences(seq)
+        .iter()
+        .enumerate()
+        .map(|(index, difference)| (index as f64, *difference as f64))
+        .collect();
+    let chart = Chart::line("differences", points);
+    match info {
+        Option::Some(info) => chart.shade_period(info),
+        Option::None => chart,
+    }
+}
+
+/// The counting function divided by `x`, i.e. `A(x)/x`, at every element.
+pub fn density_chart(seq: &[usize]) -> Chart {
+    let points = seq
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| **element > 0)
+        .map(|(index, element)| (*element as f64, (index + 1) as f64 / *element as f64))
+        .collect();
+    Chart::line("A(x)/x", points)
+}
+
+/// The residues of the elements modulo `modulus` versus their index, with the
+/// period shaded when available.
+pub fn barcode_chart(seq: &[usize], modulus: usize, info: Option<&Info>) -> Chart {
+    let points = seq
+        .iter()
+        .enumerate()
+        .map(|(index, element)| (index as f64, (element % modulus) as f64))
+        .collect();
+    let chart = Chart::dots(&format!("residues modulo {}", modulus), points);
+    match info {
+        Option::Some(info) => chart.shade_period(info),
+        Option::None => chart,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(charts: &[Chart]) -> String {
+        let mut output: Vec<u8> = Vec::new();
+        write_svg(charts, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn periods_are_shaded() {
+        let seq = [1, 2, 4, 7, 10, 13, 16, 19];
+        let svg = render(&[differences_chart(&seq, Some(&Info::new(2, 1)))]);
+
+        assert!(svg.contains(PRE_PERIOD));
+        assert!(svg.contains(PERIOD));
+    }
+
+    #[test]
+    fn charts_are_stacked() {
+        let seq = [1, 2, 4, 7, 10, 13, 16, 19];
+        let svg = render(&[density_chart(&seq), barcode_chart(&seq, 3, None)]);
+
+        assert!(svg.contains("viewBox=\"0 0 800 400\""));
+        assert!(svg.contains("residues modulo 3"));
+        assert_eq!(svg.matches("<circle").count(), seq.len());
+    }
+
+    #[test]
+    fn titles_are_escaped() {
+        let svg = render(&[Chart::line("a < b", vec![(0.0, 0.0)])]);
+
+        assert!(svg.contains("a &lt; b"));
+    }
+}
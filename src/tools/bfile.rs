@@ -0,0 +1,167 @@
+//! The `bfile` module reads sequences written down in the
+//! [b-file format](https://oeis.org/SubmittingB-Files.html) of the OEIS and
+//! compares them with generated sequences.
+//!
+//! A b-file has one term per line, as the index `n` followed by the term
+//! `a(n)`. The indices are consecutive, starting at the offset of the
+//! sequence. Blank lines and lines starting with `#` are ignored.
+//!
+//! ## Example
+//!
+//! ```
+//! # use std::io::Cursor;
+//! # use sequence::tools::bfile::{BFile, Comparison};
+//! let bfile = BFile::read(Cursor::new("# A000027\n1 1\n2 2\n3 3\n4 4\n")).unwrap();
+//! assert_eq!(bfile.compare(&[1, 2, 3]), Comparison::Agree { length: 3 });
+//! assert_eq!(
+//!     bfile.compare(&[1, 2, 4, 5]),
+//!     Comparison::Disagree { n: 3, expected: 3, actual: 4 }
+//! );
+//! ```
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::path::Path;
+
+/// The terms of an OEIS b-file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BFile {
+    /// The index of the first term.
+    pub offset: usize,
+    /// The terms, starting at `offset`.
+    pub terms: Vec<usize>,
+}
+
+impl BFile {
+    /// Reads a b-file, failing when a line is malformed, when a term does not
+    /// fit into a `usize` or when the indices are not consecutive.
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut offset = Option::None;
+        let mut terms = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("line {}: {}: {}", number + 1, reason, line),
+                )
+            };
+            let mut fields = line.split_whitespace();
+            let (n, term) = match (fields.next(), fields.next(), fields.next()) {
+                (Option::Some(n), Option::Some(term), Option::None) => (n, term),
+                _ => return Err(invalid("expected an index and a term")),
+            };
+            let n: usize = n.parse().map_err(|_| invalid("invalid index"))?;
+            let term: usize = term.parse().map_err(|_| invalid("invalid term"))?;
+            let expected = *offset.get_or_insert(n) + terms.len();
+            if n != expected {
+                return Err(invalid(&format!("expected index {}", expected)));
+            }
+            terms.push(term);
+        }
+        Ok(Self {
+            offset: offset.unwrap_or(1),
+            terms,
+        })
+    }
+
+    /// Reads the b-file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Compares the terms with the `generated` sequence up to the shorter of
+    /// both.
+    pub fn compare(&self, generated: &[usize]) -> Comparison {
+        let disagreement = self
+            .terms
+            .iter()
+            .zip(generated)
+            .position(|(expected, actual)| expected != actual);
+        match disagreement {
+            Option::Some(index) => Comparison::Disagree {
+                n: self.offset + index,
+                expected: self.terms[index],
+                actual: generated[index],
+            },
+            Option::None => Comparison::Agree {
+                length: self.terms.len().min(generated.len()),
+            },
+        }
+    }
+}
+
+/// The outcome of comparing a b-file with a generated sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// The first `length` terms agree.
+    Agree {
+        /// The number of compared terms.
+        length: usize,
+    },
+    /// The sequences differ first at index `n` of the b-file.
+    Disagree {
+        /// The index of the first difference.
+        n: usize,
+        /// The term of the b-file.
+        expected: usize,
+        /// The generated term.
+        actual: usize,
+    },
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::Agree { length } => write!(f, "agree on the first {} terms", length),
+            Comparison::Disagree {
+                n,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "disagree at n = {}: the b-file has {}, the sequence has {}",
+                n, expected, actual
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::{BFile, Comparison};
+
+    #[test]
+    fn b_files_keep_their_offset() {
+        let bfile = BFile::read(Cursor::new("\n# comment\n0 1\n1 2\n 2 4 \n")).unwrap();
+        assert_eq!(bfile.offset, 0);
+        assert_eq!(bfile.terms, vec![1, 2, 4]);
+        assert_eq!(
+            bfile.compare(&[1, 2, 3]),
+            Comparison::Disagree {
+                n: 2,
+                expected: 4,
+                actual: 3
+            }
+        );
+        assert_eq!(
+            bfile.compare(&[1, 2, 4, 8]),
+            Comparison::Agree { length: 3 }
+        );
+    }
+
+    #[test]
+    fn malformed_b_files_are_rejected() {
+        for contents in ["1 1\n3 2\n", "1 1\n2\n", "1 1 1\n", "1 x\n", "1 -2\n"] {
+            let error = BFile::read(Cursor::new(contents)).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "{:?}", contents);
+        }
+    }
+}
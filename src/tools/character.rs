@@ -1,5 +1,7 @@
 //! Provides the character of a subsumfree sequence.
 
+use crate::combinatorics::form::LinearForm;
+use crate::tools::differences;
 use crate::tools::period;
 use crate::tools::period::detect_cycle;
 use std::collections::BTreeSet as Set;
@@ -9,18 +11,27 @@ use std::fmt::Display;
 ///
 /// 1. The period info.
 /// 2. The modulus.
-/// 3. The elements only in the pre-period.
-/// 4. The elements in the period.
+/// 3. The elements in the pre-period.
+/// 4. The residues of the elements in the period.
+///
+/// Beyond the pre-period, a number is an element exactly when its residue is
+/// one of the repeating residues. It also remembers the linear form the
+/// sequence avoids, so that the claim can be verified, and whether it
+/// characterizes the complement of a sequence instead.
 #[derive(Debug)]
 pub struct Character {
     info: period::Info,
     modulus: usize,
     unique: Set<usize>,
     repeating: Set<usize>,
+    start: usize,
+    form: LinearForm,
+    complement: Option<Vec<usize>>,
 }
 
 impl Character {
-    /// Create a `Character` with all relevant information.
+    /// Create a `Character` with all relevant information, for a sequence
+    /// that avoids sums of three distinct elements.
     pub fn new(
         info: period::Info,
         modulus: usize,
@@ -32,9 +43,48 @@ impl Character {
             modulus,
             unique,
             repeating,
+            start: 0,
+            form: LinearForm::distinct(3),
+            complement: Option::None,
         }
     }
 
+    /// The pre-period and period of the differences.
+    pub fn info(&self) -> &period::Info {
+        &self.info
+    }
+
+    /// The modulus, i.e. the sum of the differences in the period.
+    pub fn modulus(&self) -> usize {
+        self.modulus
+    }
+
+    /// The residues of the elements in the period.
+    pub fn repeating(&self) -> &Set<usize> {
+        &self.repeating
+    }
+
+    /// Restricts the repeating residues to the numbers from `start` on, the
+    /// first element of the period.
+    pub fn repeating_from(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Changes the linear form that the characterized sequence avoids.
+    pub fn with_form(mut self, form: LinearForm) -> Self {
+        self.form = form;
+        self
+    }
+
+    /// Marks the character as that of the complement of a sequence beyond
+    /// its `initial` segment, whose elements are exactly the solutions of the
+    /// linear form in the sequence.
+    pub fn of_complement(mut self, initial: &[usize]) -> Self {
+        self.complement = Option::Some(initial.to_vec());
+        self
+    }
+
     /// Write a Walnut script that can check te claim that the subsumfree
     /// sequence is correctly characterized.
     pub fn write_walnut<T>(&self, name: &str, f: &mut T) -> Result<(), std::io::Error>
@@ -48,16 +98,80 @@ impl Character {
         for unique in &self.unique {
             definitions.push(format!("z={}", unique));
         }
-        for repeating in &self.repeating {
-            definitions.push(format!("$is{}(z)", repeating));
+        let residues: Vec<String> = self
+            .repeating
+            .iter()
+            .map(|repeating| format!("$is{}(z)", repeating))
+            .collect();
+        match (self.start, residues.len()) {
+            (_, 0) => {}
+            (0 | 1, _) => definitions.extend(residues),
+            (start, _) => definitions.push(format!("(z>={} & ({}))", start, residues.join(" | "))),
         }
         let definition = definitions.join(" | ");
         writeln!(f, "\ndef {} \"{}\":", name, definition)?;
-        writeln!(
-            f,
-            "\neval prop_{0} \"Az z>0 => (${0}(z) <=> z>0 & ~(E a,b,c a<b & b<c & ${0}(a) & ${0}(b) & ${0}(c) & a+b+c=z))\"::",
-            name
-        )
+        match &self.complement {
+            Option::Some(initial) => writeln!(
+                f,
+                "\neval prop_{0} \"Az z>{1} => (${0}(z) <=> ({2}))\"::",
+                name,
+                initial.last().copied().unwrap_or(0),
+                self.solution(name)
+            ),
+            Option::None => writeln!(
+                f,
+                "\neval prop_{0} \"Az z>0 => (${0}(z) <=> z>0 & ~({1}))\"::",
+                name,
+                self.solution(name)
+            ),
+        }
+    }
+
+    /// The Walnut formula that claims a solution of the linear form in the
+    /// sequence called `name`, or for a complement in the sequence made of
+    /// the initial segment and the numbers beyond it outside of `name`.
+    fn solution(&self, name: &str) -> String {
+        let coefficients = self.form.coefficients();
+        let variables: Vec<String> = (0..coefficients.len())
+            .map(|index| match index {
+                0..25 => ((b'a' + index as u8) as char).to_string(),
+                _ => format!("x{}", index),
+            })
+            .collect();
+        let mut conditions: Vec<String> = Vec::new();
+        if self.form.is_distinct() {
+            for pair in variables.windows(2) {
+                conditions.push(format!("{}<{}", pair[0], pair[1]));
+            }
+        }
+        for variable in &variables {
+            match &self.complement {
+                Option::Some(initial) => {
+                    let mut element: Vec<String> = initial
+                        .iter()
+                        .map(|seed| format!("{}={}", variable, seed))
+                        .collect();
+                    element.push(format!(
+                        "({0}>{1} & ~${2}({0}))",
+                        variable,
+                        initial.last().copied().unwrap_or(0),
+                        name
+                    ));
+                    conditions.push(format!("({})", element.join(" | ")));
+                }
+                Option::None => conditions.push(format!("${}({})", name, variable)),
+            }
+        }
+        let terms: Vec<String> = coefficients
+            .iter()
+            .zip(&variables)
+            .map(|(coefficient, variable)| match coefficient {
+                1 => variable.clone(),
+                _ => format!("{}*{}", coefficient, variable),
+            })
+            .collect();
+        conditions.push(format!("{}=z", terms.join("+")));
+        format!("E {} {}", variables.join(","), conditions.join(" & "))
     }
 }
 
@@ -71,31 +185,227 @@ impl Display for Character {
     }
 }
 
-/// Determine the `Character` of a sequence.
+/// Determine the `Character` of a sequence that avoids sums of three distinct
+/// elements.
 pub fn determine_character(seq: &[usize]) -> Option<Character> {
-    let differences: Vec<usize> = seq.windows(2).map(|t| t[1] - t[0]).collect();
+    determine_character_of(seq, LinearForm::distinct(3))
+}
+
+/// Determine the `Character` of a sequence that avoids the linear `form`.
+///
+/// ```
+/// # use sequence::combinatorics::form::{LinearForm, Sequence};
+/// # use sequence::tools::character::determine_character_of;
+/// let seq: Vec<usize> = Sequence::with_maximum(vec![1], vec![1, 2], 100).collect();
+/// let character = determine_character_of(&seq, LinearForm::new(vec![1, 2])).unwrap();
+/// assert_eq!(character.to_string(), "(0, 2) 6 {} {1, 2}");
+/// ```
+pub fn determine_character_of(seq: &[usize], form: LinearForm) -> Option<Character> {
+    let differences = differences(seq);
 
     match detect_cycle(&differences) {
         Some(info) if info.check(&differences) => {
             let modulus: usize = differences[info.pre_period..(info.pre_period + info.period)]
                 .iter()
                 .sum();
-            let mod_seq: Vec<usize> = seq.iter().map(|n| n % modulus).collect();
-            let repeating_elements: Set<usize> = mod_seq
-                [info.pre_period..(info.pre_period + info.period)]
-                .iter()
-                .cloned()
-                .collect();
-            let prefix: Set<usize> = mod_seq[..info.pre_period].iter().cloned().collect();
-            let unique_elements: Set<usize> =
-                prefix.difference(&repeating_elements).cloned().collect();
-            Option::Some(Character::new(
-                info,
-                modulus,
-                unique_elements,
-                repeating_elements,
-            ))
+            Option::Some(character_of(seq, info, modulus, form))
         }
         _ => Option::None,
     }
 }
+
+/// The `Character` of a sequence whose differences are periodic as `info`
+/// describes, with the sum of the differences in the period as `modulus`.
+fn character_of(seq: &[usize], info: period::Info, modulus: usize, form: LinearForm) -> Character {
+    let period = &seq[info.pre_period..(info.pre_period + info.period)];
+    let repeating_elements: Set<usize> = period.iter().map(|n| n % modulus).collect();
+    let unique_elements: Set<usize> = seq[..info.pre_period].iter().cloned().collect();
+    let start = period[0];
+    Character::new(info, modulus, unique_elements, repeating_elements)
+        .repeating_from(start)
+        .with_form(form)
+}
+
+/// The characteristic word of a sequence below `ceiling`: whether each number
+/// from zero on is an element.
+pub fn characteristic(seq: &[usize], ceiling: usize) -> Vec<usize> {
+    let length = seq.last().map_or(ceiling, |last| ceiling.max(last + 1));
+    let mut word = vec![0; length];
+    for n in seq {
+        word[*n] = 1;
+    }
+    word
+}
+
+/// Try to find the cycle of the characteristic word of a sequence below
+/// `ceiling`. Its pre-period is the threshold beyond which the membership of
+/// a number only depends on its residue, and its period is the modulus.
+pub fn detect_characteristic_cycle(seq: &[usize], ceiling: usize) -> Option<period::Info> {
+    let word = characteristic(seq, ceiling);
+    detect_cycle(&word).filter(|info| info.check(&word))
+}
+
+/// Determine the `Character` of a sequence below `ceiling` that avoids the
+/// linear `form` from its characteristic word instead of its differences.
+///
+/// The modulus is the period of the word, so long gaps between the elements
+/// only need a longer word, not more elements.
+///
+/// ```
+/// # use sequence::combinatorics::form::{LinearForm, Sequence};
+/// # use sequence::tools::character::determine_character_from_characteristic;
+/// let seq: Vec<usize> = Sequence::with_maximum(vec![1], vec![1, 2], 100).collect();
+/// let character =
+///     determine_character_from_characteristic(&seq, 100, LinearForm::new(vec![1, 2])).unwrap();
+/// assert_eq!(character.to_string(), "(0, 2) 6 {} {1, 2}");
+/// ```
+pub fn determine_character_from_characteristic(
+    seq: &[usize],
+    ceiling: usize,
+    form: LinearForm,
+) -> Option<Character> {
+    let cycle = detect_characteristic_cycle(seq, ceiling)?;
+    // the elements from the threshold on have periodic differences, with one
+    // difference in the period for every element in it
+    let pre_period = seq.partition_point(|n| *n < cycle.pre_period);
+    let period = seq[pre_period..]
+        .iter()
+        .take_while(|n| **n < cycle.pre_period + cycle.period)
+        .count();
+    if period == 0 {
+        return Option::None;
+    }
+    let info = period::Info::new(pre_period, period);
+    Option::Some(character_of(seq, info, cycle.period, form))
+}
+
+/// Determine the `Character` of the complement beyond the `initial` segment
+/// of a sequence that avoids the linear `form`.
+///
+/// ```
+/// # use sequence::combinatorics::form::LinearForm;
+/// # use sequence::tools::character::determine_complement_character;
+/// let complement: Vec<usize> = (3..100).filter(|n| n % 3 == 0).collect();
+/// let character =
+///     determine_complement_character(&complement, &[1, 2], LinearForm::distinct(2)).unwrap();
+/// assert_eq!(character.to_string(), "(0, 1) 3 {} {0}");
+/// ```
+pub fn determine_complement_character(
+    complement: &[usize],
+    initial: &[usize],
+    form: LinearForm,
+) -> Option<Character> {
+    determine_character_of(complement, form).map(|character| character.of_complement(initial))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinatorics::combination::Sequence;
+
+    fn walnut(character: &Character) -> String {
+        let mut output: Vec<u8> = Vec::new();
+        character.write_walnut("seq", &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn walnut_claims_sums_of_distinct_elements_by_default() {
+        let seq: Vec<usize> = vec![1, 2, 4, 7, 10, 13, 16, 19, 22, 25, 28, 31];
+        let character = determine_character(&seq).unwrap();
+
+        assert!(
+            walnut(&character)
+                .contains("~(E a,b,c a<b & b<c & $seq(a) & $seq(b) & $seq(c) & a+b+c=z)")
+        );
+    }
+
+    #[test]
+    fn walnut_claims_solutions_of_the_linear_form() {
+        let seq: Vec<usize> = vec![1, 2, 7, 8, 13, 14, 19, 20, 25, 26, 31, 32];
+        let character = determine_character_of(&seq, LinearForm::new(vec![1, 2])).unwrap();
+
+        assert!(walnut(&character).contains("~(E a,b $seq(a) & $seq(b) & a+2*b=z)"));
+    }
+
+    #[test]
+    fn characteristic_words_cope_with_long_gaps() {
+        // twenty differences of one followed by a few gaps of ten
+        let seq: Vec<usize> = (1..=20).chain((21..100).step_by(10)).collect();
+        assert!(determine_character(&seq).is_none());
+
+        let cycle = detect_characteristic_cycle(&seq, 100).unwrap();
+        assert_eq!(cycle, period::Info::new(21, 10));
+        let character =
+            determine_character_from_characteristic(&seq, 100, LinearForm::distinct(3)).unwrap();
+        let unique: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+        assert_eq!(
+            character.to_string(),
+            format!("(20, 1) 10 {{{}}} {{1}}", unique.join(", "))
+        );
+        assert!(walnut(&character).contains("z=19 | z=20 | (z>=21 & ($is1(z)))"));
+    }
+
+    #[test]
+    fn walnut_claims_the_repeating_residues_from_the_period_on() {
+        // 3 has the repeating residue 1 but is not an element
+        let seq: Vec<usize> = vec![1, 5, 7, 9, 11, 13, 15, 17, 19, 21];
+        let character = determine_character_of(&seq, LinearForm::distinct(2)).unwrap();
+
+        assert_eq!(character.to_string(), "(1, 1) 2 {1} {1}");
+        assert!(walnut(&character).contains("def seq \"z=1 | (z>=5 & ($is1(z)))\":"));
+    }
+
+    #[test]
+    fn characteristic_words_extend_to_the_ceiling() {
+        assert_eq!(characteristic(&[1, 3], 6), vec![0, 1, 0, 1, 0, 0]);
+        assert_eq!(characteristic(&[1, 3], 2), vec![0, 1, 0, 1]);
+        // a finite sequence has no repeating elements
+        assert!(
+            determine_character_from_characteristic(&[1, 3], 100, LinearForm::distinct(3))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn walnut_claims_solutions_outside_of_the_complement() {
+        let complement: Vec<usize> = (3..100).filter(|n| n % 3 == 0).collect();
+        let character =
+            determine_complement_character(&complement, &[1, 2], LinearForm::distinct(2)).unwrap();
+
+        assert!(walnut(&character).contains(
+            "Az z>2 => ($seq(z) <=> (E a,b a<b & (a=1 | a=2 | (a>2 & ~$seq(a))) & \
+             (b=1 | b=2 | (b>2 & ~$seq(b))) & a+b=z))"
+        ));
+    }
+
+    #[test]
+    fn walnut_claims_the_complement_beyond_a_seed_with_gaps() {
+        let initial: Vec<usize> = vec![2, 3, 7];
+        let seq: Vec<usize> = Sequence::with_maximum(initial.clone(), 300).collect();
+        let complement: Vec<usize> = (8..300).filter(|n| !seq.contains(n)).collect();
+        let character =
+            determine_complement_character(&complement, &initial, LinearForm::distinct(3)).unwrap();
+
+        assert!(walnut(&character).contains(
+            "Az z>7 => ($seq(z) <=> (E a,b,c a<b & b<c & (a=2 | a=3 | a=7 | (a>7 & ~$seq(a))) & \
+             (b=2 | b=3 | b=7 | (b>7 & ~$seq(b))) & (c=2 | c=3 | c=7 | (c>7 & ~$seq(c))) & \
+             a+b+c=z))"
+        ));
+        // the claim holds when the variables range over the sequence as it
+        // defines it
+        let elements: Vec<usize> = (1..300)
+            .filter(|v| initial.contains(v) || (*v > 7 && !complement.contains(v)))
+            .collect();
+        assert_eq!(elements, seq);
+        let mut sums: Set<usize> = Set::new();
+        for (i, a) in elements.iter().enumerate() {
+            for (j, b) in elements.iter().enumerate().skip(i + 1) {
+                sums.extend(elements[(j + 1)..].iter().map(|c| a + b + c));
+            }
+        }
+        for z in 8..300 {
+            assert_eq!(complement.contains(&z), sums.contains(&z), "{}", z);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! The `complement` module streams the numbers that a sequence leaves out
+//! beyond its initial segment.
+//!
+//! These are exactly the numbers that the engines reject because they are
+//! expressable, unless numbers are excluded by the [`Config`](crate::Config).
+//! The gaps within the initial segment are left out, since they are chosen
+//! rather than expressable.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::combinatorics::combination::Sequence;
+//! # use sequence::tools::complement::Complement;
+//! let sequence = Sequence::with_maximum(vec![1, 2, 3], 12);
+//! let actual: Vec<usize> = Complement::new(sequence, 3, 12).collect();
+//! let expected: Vec<usize> = vec![6, 7, 8, 9, 10, 11];
+//! assert_eq!(actual, expected);
+//! ```
+
+use crate::budget::{Budgeted, Counters, Exhaustion, Generator, Instrumented};
+
+/// Iterates over the numbers beyond a start, usually the last element of the
+/// initial segment, and below a ceiling that are not in an increasing
+/// sequence.
+#[derive(Debug)]
+pub struct Complement<I>
+where
+    I: Iterator<Item = usize>,
+{
+    sequence: I,
+    upcoming: Option<usize>,
+    candidate: usize,
+    ceiling: usize,
+    ended: fn(&I) -> bool,
+}
+
+impl<I> Complement<I>
+where
+    I: Iterator<Item = usize>,
+{
+    /// Creates the complement of the increasing `sequence` beyond `start`
+    /// and below `ceiling`.
+    pub fn new(mut sequence: I, start: usize, ceiling: usize) -> Self {
+        Self {
+            upcoming: sequence.next(),
+            sequence,
+            candidate: start + 1,
+            ceiling,
+            ended: |_| false,
+        }
+    }
+}
+
+impl<I> Complement<I>
+where
+    I: Generator,
+{
+    /// Creates the complement of the increasing `sequence` beyond `start`
+    /// and below `ceiling`, which ends early when the budget of the sequence
+    /// runs out.
+    pub fn budgeted(sequence: I, start: usize, ceiling: usize) -> Self {
+        Self {
+            ended: |sequence| sequence.exhausted().is_some(),
+            ..Self::new(sequence, start, ceiling)
+        }
+    }
+}
+
+impl<I> Budgeted for Complement<I>
+where
+    I: Generator,
+{
+    fn exhausted(&self) -> Option<Exhaustion> {
+        self.sequence.exhausted()
+    }
+}
+
+impl<I> Instrumented for Complement<I>
+where
+    I: Generator,
+{
+    fn counters(&self) -> Counters {
+        self.sequence.counters()
+    }
+}
+
+impl<I> Iterator for Complement<I>
+where
+    I: Iterator<Item = usize>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.candidate < self.ceiling {
+            let candidate = self.candidate;
+            self.candidate += 1;
+            while self.upcoming.is_some_and(|element| element < candidate) {
+                self.upcoming = self.sequence.next();
+            }
+            if self.upcoming.is_none() && (self.ended)(&self.sequence) {
+                // the numbers beyond the last element are unknown
+                self.candidate = self.ceiling;
+                return Option::None;
+            }
+            if self.upcoming == Option::Some(candidate) {
+                self.upcoming = self.sequence.next();
+            } else {
+                return Option::Some(candidate);
+            }
+        }
+        Option::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Complement;
+
+    #[test]
+    fn complement_and_sequence_partition_the_numbers_beyond_the_start() {
+        let sequence = [2, 3, 5, 7, 11, 13];
+        let complement: Vec<usize> = Complement::new(sequence.iter().cloned(), 3, 15).collect();
+
+        assert_eq!(complement, vec![4, 6, 8, 9, 10, 12, 14]);
+        // the gaps within the initial segment are no part of the complement
+        let complement: Vec<usize> = Complement::new(sequence.iter().cloned(), 5, 15).collect();
+        assert_eq!(complement, vec![6, 8, 9, 10, 12, 14]);
+    }
+}
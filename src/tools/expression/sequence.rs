@@ -3,8 +3,9 @@
 //! Due to the limitation of `express` can only start with initial segment of
 //! three.
 
-use crate::tools::ItemCandidate;
+use crate::Error;
 use crate::tools::expression::{ExpressionResult, express};
+use crate::tools::{ItemCandidate, validate};
 use std::collections::BTreeSet;
 
 /// The sequence
@@ -17,22 +18,44 @@ pub struct Sequence {
 
 impl Sequence {
     /// Creates a sequence with an initial segment
+    ///
+    /// # Panics
+    /// Panics when the initial segment is invalid, see [`Sequence::try_new`].
     pub fn new(initial: Vec<usize>) -> Self {
+        Sequence::try_new(initial).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a sequence with an initial segment, failing when the initial
+    /// segment is empty, not strictly increasing or not subsumfree.
+    pub fn try_new(initial: Vec<usize>) -> Result<Self, Error> {
         Sequence::initialize(initial, Option::None)
     }
 
     /// Create a sequence with an inital segment and a ceiling
+    ///
+    /// # Panics
+    /// Panics when the initial segment is invalid, see [`Sequence::try_new`].
     pub fn with_maximum(initial: Vec<usize>, maximum: usize) -> Self {
+        Sequence::try_with_maximum(initial, maximum).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a sequence with an inital segment and a ceiling, failing when
+    /// the initial segment is invalid.
+    pub fn try_with_maximum(initial: Vec<usize>, maximum: usize) -> Result<Self, Error> {
         Sequence::initialize(initial, Option::Some(ItemCandidate::Element(maximum)))
     }
 
-    fn initialize(initial: Vec<usize>, maximum: Option<ItemCandidate>) -> Self {
+    fn initialize(initial: Vec<usize>, maximum: Option<ItemCandidate>) -> Result<Self, Error> {
+        validate(&initial, |prefix, element| {
+            let elements: BTreeSet<usize> = prefix.iter().cloned().collect();
+            express(element, &elements) != ExpressionResult::Unexpressable
+        })?;
         let elements: BTreeSet<usize> = initial.iter().cloned().collect();
-        Self {
+        Ok(Self {
             current: ItemCandidate::Index(0, initial),
             elements,
             maximum,
-        }
+        })
     }
 }
 
@@ -89,4 +112,15 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn sequence_rejects_seeds_that_are_not_subsumfree() {
+        assert_eq!(
+            Sequence::try_new(vec![1, 2, 3, 6]).err(),
+            Some(Error::Expressable {
+                index: 3,
+                element: 6
+            })
+        );
+    }
 }
@@ -1,12 +1,67 @@
 //! The `tools` module contains various utility data structures and methods
 //! that work on subsumfree sequences.
 
+pub mod bfile;
 pub mod character;
+pub mod complement;
 pub mod expression;
 pub mod period;
+pub mod recurrence;
+pub mod residue;
 
+use crate::Error;
 use std::cmp::Ordering;
 
+/// Validates an initial segment.
+///
+/// An initial segment must be non-empty, strictly increasing and none of its
+/// elements may be expressable by the elements that precede it. The
+/// `expressable` predicate decides the latter given the preceding elements and
+/// the element under scrutiny.
+pub fn validate<F>(initial: &[usize], expressable: F) -> Result<(), Error>
+where
+    F: Fn(&[usize], usize) -> bool,
+{
+    if initial.is_empty() {
+        return Err(Error::EmptyInitialSegment);
+    }
+    for (index, window) in initial.windows(2).enumerate() {
+        let (previous, element) = (window[0], window[1]);
+        if previous == element {
+            return Err(Error::Duplicated {
+                index: index + 1,
+                element,
+            });
+        }
+        if previous > element {
+            return Err(Error::Unsorted {
+                index: index + 1,
+                previous,
+                element,
+            });
+        }
+    }
+    for (index, element) in initial.iter().enumerate() {
+        if expressable(&initial[..index], *element) {
+            return Err(Error::Expressable {
+                index,
+                element: *element,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The first differences of a sequence.
+///
+/// ```
+/// # use sequence::tools::differences;
+/// assert_eq!(differences(&[1, 2, 4, 7]), vec![1, 2, 3]);
+/// ```
+pub fn differences(seq: &[usize]) -> Vec<usize> {
+    seq.windows(2).map(|t| t[1] - t[0]).collect()
+}
+
 /// An `ItemCandidate` keeps track which element is under scrutiny.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ItemCandidate {
@@ -59,7 +114,8 @@ impl PartialOrd for ItemCandidate {
 
 #[cfg(test)]
 mod tests {
-    use super::ItemCandidate;
+    use super::{ItemCandidate, validate};
+    use crate::Error;
 
     #[test]
     fn item_candidates_are_ordered() {
@@ -71,4 +127,36 @@ mod tests {
         assert!(ItemCandidate::Element(37) < ItemCandidate::Element(51));
         assert!(ItemCandidate::Element(51) > ItemCandidate::Element(37));
     }
+
+    #[test]
+    fn validate_rejects_malformed_initial_segments() {
+        let never = |_: &[usize], _: usize| false;
+        assert_eq!(validate(&[], never), Err(Error::EmptyInitialSegment));
+        assert_eq!(
+            validate(&[1, 5, 3], never),
+            Err(Error::Unsorted {
+                index: 2,
+                previous: 5,
+                element: 3
+            })
+        );
+        assert_eq!(
+            validate(&[1, 3, 3], never),
+            Err(Error::Duplicated {
+                index: 2,
+                element: 3
+            })
+        );
+        assert_eq!(
+            validate(&[1, 2, 3], |prefix: &[usize], n| prefix
+                .iter()
+                .sum::<usize>()
+                == n),
+            Err(Error::Expressable {
+                index: 2,
+                element: 3
+            })
+        );
+        assert_eq!(validate(&[1, 3, 5], never), Ok(()));
+    }
 }
@@ -73,7 +73,7 @@ mod tests {
                 assert_eq!(info, Info::new(0, 3));
             }
             _ => {
-                assert!(false);
+                panic!("expected a cycle to be detected");
             }
         }
     }
@@ -89,7 +89,7 @@ mod tests {
                 assert_eq!(info, Info::new(5, 3));
             }
             Option::None => {
-                assert!(false);
+                panic!("expected a cycle to be detected");
             }
         }
     }
@@ -0,0 +1,316 @@
+//! The `recurrence` module guesses linear recurrences and generating functions
+//! of sequences.
+//!
+//! A sequence whose differences are eventually periodic, with period `p` and
+//! modulus `m`, satisfies `a(n) = a(n-1) + a(n-p) - a(n-p-1)` eventually, so
+//! its generating function is rational with denominator `(1-x)(1-x^p)`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::tools::character::determine_character;
+//! # use sequence::tools::recurrence::{generating_function, guess_recurrence};
+//! let seq: Vec<usize> = vec![1, 2, 4, 7, 10, 13, 16, 19, 22, 25, 28, 31];
+//! let recurrence = guess_recurrence(&seq).unwrap();
+//! assert_eq!(recurrence.to_string(), "a(n) = 2*a(n-1) - a(n-2) for n >= 5");
+//!
+//! let character = determine_character(&seq).unwrap();
+//! let gf = generating_function(&seq, &character).unwrap();
+//! assert_eq!(gf.to_string(), "(x + x^3 + x^4)/(1-x)^2");
+//! ```
+
+use std::fmt::Display;
+
+use crate::tools::character::Character;
+
+/// The prime modulus of the field in which the Berlekamp–Massey algorithm
+/// runs.
+const PRIME: u64 = (1 << 61) - 1;
+
+fn multiply(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % PRIME as u128) as u64
+}
+
+fn inverse(a: u64) -> u64 {
+    let (mut base, mut exponent, mut result) = (a, PRIME - 2, 1);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(result, base);
+        }
+        base = multiply(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// The shortest linear feedback shift register that generates `seq` modulo
+/// [`PRIME`], as its connection polynomial `C` with `C[0] = 1` and its
+/// length `L`, i.e. `sum C[i] seq[n-i] = 0` for all `n >= L`.
+fn berlekamp_massey(seq: &[u64]) -> (Vec<u64>, usize) {
+    let mut connection = vec![1];
+    let mut previous = vec![1];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut last = 1;
+    for n in 0..seq.len() {
+        let discrepancy = (0..connection.len().min(n + 1)).fold(0, |sum, i| {
+            (sum + multiply(connection[i], seq[n - i])) % PRIME
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let factor = multiply(discrepancy, inverse(last));
+        let mut next = connection.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, coefficient) in previous.iter().enumerate() {
+            let term = multiply(factor, *coefficient);
+            next[i + shift] = (next[i + shift] + PRIME - term) % PRIME;
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = connection;
+            last = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        connection = next;
+    }
+    connection.truncate(length + 1);
+    (connection, length)
+}
+
+/// A linear recurrence `a(n) = c(1) a(n-1) + ... + c(d) a(n-d)` that holds
+/// from index `start` on, where the first element is `a(1)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// The coefficients `c(1), ..., c(d)`.
+    pub coefficients: Vec<i64>,
+    /// The first index for which the recurrence holds.
+    pub start: usize,
+}
+
+impl Recurrence {
+    /// Whether the recurrence holds for `seq`.
+    pub fn check(&self, seq: &[usize]) -> bool {
+        (self.start.max(1) - 1..seq.len()).all(|n| {
+            let sum = self
+                .coefficients
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i < n)
+                .map(|(i, c)| *c as i128 * seq[n - i - 1] as i128)
+                .sum::<i128>();
+            sum == seq[n] as i128
+        })
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<(i64, String)> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, format!("a(n-{})", i + 1)))
+            .collect();
+        write!(f, "a(n) = {} for n >= {}", sum(&terms), self.start)
+    }
+}
+
+/// Guesses the shortest linear recurrence with integer coefficients that
+/// generates `seq`, using the Berlekamp–Massey algorithm.
+///
+/// Fails when the recurrence is not supported by at least twice as many
+/// elements as its length, or when it does not have integer coefficients.
+pub fn guess_recurrence(seq: &[usize]) -> Option<Recurrence> {
+    let residues: Vec<u64> = seq.iter().map(|n| *n as u64 % PRIME).collect();
+    let (connection, length) = berlekamp_massey(&residues);
+    if 2 * length > seq.len() {
+        return Option::None;
+    }
+    // lift the coefficients to the integers closest to zero
+    let mut coefficients: Vec<i64> = connection[1..]
+        .iter()
+        .map(|c| match PRIME - c {
+            negated if negated <= PRIME / 2 => negated as i64,
+            _ => -(*c as i64),
+        })
+        .collect();
+    while coefficients.last() == Option::Some(&0) {
+        coefficients.pop();
+    }
+    let recurrence = Recurrence {
+        coefficients,
+        start: length + 1,
+    };
+    if recurrence.check(seq) {
+        Option::Some(recurrence)
+    } else {
+        Option::None
+    }
+}
+
+/// The rational generating function `P(x)/((1-x)(1-x^p))` of a sequence
+/// `a(1), a(2), ...`, i.e. of `sum a(n) x^n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratingFunction {
+    /// The coefficients of the numerator `P`, starting with the constant.
+    pub numerator: Vec<i64>,
+    /// The period `p` of the differences.
+    pub period: usize,
+}
+
+impl GeneratingFunction {
+    /// The coefficients `a(1), ..., a(length)` of the power series.
+    pub fn series(&self, length: usize) -> Vec<i64> {
+        let coefficient = |n: usize| self.numerator.get(n).cloned().unwrap_or(0);
+        // divide by 1-x, then by 1-x^p
+        let mut sums: Vec<i64> = Vec::with_capacity(length + 1);
+        for n in 0..=length {
+            let previous = sums.last().cloned().unwrap_or(0);
+            sums.push(previous + coefficient(n));
+        }
+        let mut series = sums.clone();
+        for n in self.period..=length {
+            series[n] += series[n - self.period];
+        }
+        series.split_off(1)
+    }
+}
+
+impl Display for GeneratingFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<(i64, String)> = self
+            .numerator
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, power(i)))
+            .collect();
+        let denominator = match self.period {
+            1 => "(1-x)^2".to_string(),
+            p => format!("((1-x)*(1-{}))", power(p)),
+        };
+        write!(f, "({})/{}", sum(&terms), denominator)
+    }
+}
+
+/// The rational generating function of `seq`, whose differences are
+/// eventually periodic as its `character` describes.
+///
+/// Since `a(n) - a(n-1) - a(n-p) + a(n-p-1)` vanishes once the differences
+/// involved are periodic, the numerator is determined by the elements up to
+/// the end of the first period. Fails when `seq` does not reach it.
+pub fn generating_function(seq: &[usize], character: &Character) -> Option<GeneratingFunction> {
+    let info = character.info();
+    let period = info.period;
+    let degree = info.pre_period + period + 1;
+    if seq.len() < degree {
+        return Option::None;
+    }
+    // the coefficient of x^n in (1-x)(1-x^p) sum a(n) x^n
+    let a = |n: usize| match n {
+        0 => 0,
+        n => seq[n - 1] as i64,
+    };
+    let mut numerator: Vec<i64> = (0..=degree)
+        .map(|n| {
+            let shifted = |k: usize| n.checked_sub(k).map(a).unwrap_or(0);
+            a(n) - shifted(1) - shifted(period) + shifted(period + 1)
+        })
+        .collect();
+    while numerator.last() == Option::Some(&0) {
+        numerator.pop();
+    }
+    Option::Some(GeneratingFunction { numerator, period })
+}
+
+/// `x^n` in plain text.
+fn power(n: usize) -> String {
+    match n {
+        0 => "1".to_string(),
+        1 => "x".to_string(),
+        n => format!("x^{}", n),
+    }
+}
+
+/// The sum of the nonzero terms with integer coefficients in plain text.
+fn sum(terms: &[(i64, String)]) -> String {
+    let mut result = String::new();
+    for (coefficient, term) in terms.iter().filter(|(c, _)| *c != 0) {
+        let sign = match (result.is_empty(), *coefficient < 0) {
+            (true, true) => "-",
+            (true, false) => "",
+            (false, true) => " - ",
+            (false, false) => " + ",
+        };
+        let magnitude = match (coefficient.unsigned_abs(), term.as_str()) {
+            (magnitude, "1") => magnitude.to_string(),
+            (1, term) => term.to_string(),
+            (magnitude, term) => format!("{}*{}", magnitude, term),
+        };
+        result.push_str(sign);
+        result.push_str(&magnitude);
+    }
+    if result.is_empty() {
+        result.push('0');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinatorics::combination::Sequence;
+    use crate::combinatorics::form::LinearForm;
+    use crate::tools::character::determine_character_of;
+
+    #[test]
+    fn recurrences_of_eventually_periodic_differences() {
+        let seq: Vec<usize> = vec![1, 2, 7, 8, 13, 14, 19, 20, 25, 26, 31, 32];
+        let recurrence = guess_recurrence(&seq).unwrap();
+
+        assert_eq!(recurrence.coefficients, vec![1, 1, -1]);
+        assert_eq!(
+            recurrence.to_string(),
+            "a(n) = a(n-1) + a(n-2) - a(n-3) for n >= 4"
+        );
+    }
+
+    #[test]
+    fn recurrences_need_enough_elements() {
+        assert_eq!(guess_recurrence(&[1, 2, 7, 8, 13]), Option::None);
+        let recurrence = guess_recurrence(&[1, 2, 4, 7, 10, 13, 16, 19, 22, 25]).unwrap();
+        assert_eq!(recurrence.coefficients, vec![2, -1]);
+        assert_eq!(recurrence.start, 5);
+        let fibonacci = [1, 1, 2, 3, 5, 8, 13, 21];
+        let recurrence = guess_recurrence(&fibonacci).unwrap();
+        assert_eq!(recurrence.coefficients, vec![1, 1]);
+        assert!(recurrence.check(&fibonacci));
+        assert!(!recurrence.check(&[1, 1, 2, 4]));
+    }
+
+    #[test]
+    fn generating_functions_have_period_denominators() {
+        let seq: Vec<usize> = vec![1, 2, 7, 8, 13, 14, 19, 20, 25, 26, 31, 32];
+        let character = determine_character_of(&seq, LinearForm::new(vec![1, 2])).unwrap();
+        let gf = generating_function(&seq, &character).unwrap();
+
+        assert_eq!(gf.numerator, vec![0, 1, 1, 4]);
+        assert_eq!(gf.to_string(), "(x + x^2 + 4*x^3)/((1-x)*(1-x^2))");
+        assert_eq!(generating_function(&seq[..2], &character), Option::None);
+    }
+
+    #[test]
+    fn generating_functions_expand_to_the_sequence() {
+        for initial in [[1, 5], [3, 5], [2, 7], [3, 7], [4, 7], [5, 8], [1, 2]] {
+            let seq: Vec<usize> = Sequence::with_maximum(initial.to_vec(), 300).collect();
+            let character = determine_character_of(&seq, LinearForm::distinct(2)).unwrap();
+            let gf = generating_function(&seq, &character).unwrap();
+            let expected: Vec<i64> = seq.iter().map(|n| *n as i64).collect();
+
+            assert_eq!(gf.series(seq.len()), expected, "{:?}", initial);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! The `residue` module detects when the membership of the residues modulo a
+//! modulus becomes periodic.
+//!
+//! A number `n` is in an eventually periodic sequence, beyond some threshold,
+//! exactly when its residue modulo `m` is. Unlike the differences, which are
+//! indexed by the elements, the residues are indexed by the numbers, so a few
+//! sporadic elements early on do not spoil the detection.
+//!
+//! ## Example
+//!
+//! ```
+//! # use sequence::tools::residue::detect_residues;
+//! let seq: Vec<usize> = vec![1, 2, 4, 7, 10, 13, 16, 19, 22, 25, 28, 31];
+//! let residues = detect_residues(&seq, 10).unwrap();
+//! assert_eq!(residues.to_string(), "3 beyond 2 {1}");
+//! ```
+
+use std::collections::BTreeSet as Set;
+use std::fmt::Display;
+
+/// The residues modulo `modulus` of the elements beyond `threshold`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Residues {
+    /// The modulus.
+    pub modulus: usize,
+    /// The largest number whose membership does not follow its residue.
+    pub threshold: usize,
+    /// The residues of the elements beyond the threshold.
+    pub residues: Set<usize>,
+}
+
+impl Display for Residues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} beyond {} {:?}",
+            self.modulus, self.threshold, self.residues
+        )
+    }
+}
+
+/// Detects the periodicity of the residues of `seq` modulo `modulus`.
+///
+/// The membership of the numbers up to the last element is known. Its
+/// residues are periodic when every residue class is eventually either in
+/// the sequence or not, for at least two periods and for at least half of the
+/// numbers up to the last element.
+pub fn residues_modulo(seq: &[usize], modulus: usize) -> Option<Residues> {
+    let last = *seq.last()?;
+    if modulus == 0 {
+        return Option::None;
+    }
+    let members: Set<usize> = seq.iter().cloned().collect();
+    let mut threshold = 0;
+    let mut residues = Set::new();
+    // walk every residue class down from the last number in it, until its
+    // membership changes
+    for top in (last.saturating_sub(modulus) + 1)..=last {
+        let member = members.contains(&top);
+        if member {
+            residues.insert(top % modulus);
+        }
+        let mut n = top;
+        while n > modulus && members.contains(&(n - modulus)) == member {
+            n -= modulus;
+        }
+        if n > modulus {
+            threshold = threshold.max(n - modulus);
+        }
+    }
+    if threshold + 2 * modulus > last || 2 * threshold > last {
+        return Option::None;
+    }
+    Option::Some(Residues {
+        modulus,
+        threshold,
+        residues,
+    })
+}
+
+/// Searches the moduli up to `bound` for periodic residues of `seq`.
+///
+/// The best modulus has the smallest threshold, and is the smallest one among
+/// those with the same threshold.
+pub fn detect_residues(seq: &[usize], bound: usize) -> Option<Residues> {
+    (1..=bound)
+        .filter_map(|modulus| residues_modulo(seq, modulus))
+        .min_by_key(|residues| (residues.threshold, residues.modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_residues, residues_modulo};
+
+    #[test]
+    fn sporadic_elements_only_move_the_threshold() {
+        // the differences 1, 22, 1, 6, 1, 6, ... need a long prefix to cycle
+        let mut seq: Vec<usize> = vec![1, 2, 24];
+        seq.extend((25..200).filter(|n| n % 6 == 1 || n % 6 == 2));
+        let residues = detect_residues(&seq, 20).unwrap();
+
+        assert_eq!(residues.modulus, 6);
+        assert_eq!(residues.threshold, 24);
+        assert_eq!(
+            residues.residues.into_iter().collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn residues_need_two_periods() {
+        let seq: Vec<usize> = vec![1, 2, 7, 8, 13, 14];
+        assert_eq!(
+            residues_modulo(&seq, 6).map(|r| r.threshold),
+            Option::Some(0)
+        );
+        assert_eq!(residues_modulo(&seq, 4), Option::None);
+        assert_eq!(residues_modulo(&seq, 0), Option::None);
+        // a run at the end is no evidence
+        assert_eq!(residues_modulo(&[1, 5, 9, 10], 1), Option::None);
+        assert_eq!(detect_residues(&[], 10), Option::None);
+    }
+}